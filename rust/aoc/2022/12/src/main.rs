@@ -1,5 +1,5 @@
 #![allow(dead_code)]
-use challenges_common::graph::{astar, Coord, Grid, Step};
+use challenges_common::graph::{Coord, Grid, Neighbourhood};
 use itertools::Itertools;
 
 fn main() {
@@ -40,35 +40,23 @@ fn read_map(location: &[&str]) -> Map {
 }
 
 fn part1(map: &Map) -> usize {
-    let found_path = astar(
-        map.start,
-        |from| {
-            map.accessible_nodes_from(from).map(|coord| Step {
-                to: coord,
-                additional_cost: 1,
-            })
-        },
-        |coord| coord == &map.exit,
-        |coord| coord.manhattan_dist_to(&map.exit),
-    );
-
-    found_path.unwrap().cost
+    map.grid
+        .shortest_path(map.start, map.exit, |&from, &to, _| {
+            (from + 1 >= to).then_some(1)
+        })
+        .unwrap()
+        .cost
 }
 
 fn part2(map: &Map) -> usize {
-    astar(
-        map.exit,
-        |to| {
-            map.node_accessing(to).map(|coord| Step {
-                additional_cost: 1,
-                to: coord,
-            })
-        },
-        |coord| map.grid.get(coord).unwrap() == &b'a',
-        |_| 0,
-    )
-    .unwrap()
-    .cost
+    map.grid
+        .shortest_path_to_any(
+            map.exit,
+            |_, &height| height == b'a',
+            |&from, &to, _| (to + 1 >= from).then_some(1),
+        )
+        .unwrap()
+        .cost
 }
 
 struct Map {
@@ -105,7 +93,7 @@ impl Map {
         filter: impl Fn(&(Coord, &u8)) -> bool + 'a,
     ) -> impl Iterator<Item = Coord> + 'a {
         self.grid
-            .neighbours(from)
+            .neighbours(from, &Neighbourhood::VonNeumann)
             .filter(filter)
             .map(|(to_coord, _)| to_coord)
     }
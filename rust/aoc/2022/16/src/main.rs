@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use anyhow::{anyhow, Result};
 use lazy_regex::regex_captures;
 
-use challenges_common::graph::{astar, Step};
+use challenges_common::graph::{dijkstra_all, Step};
 
 fn main() {
     let map = parse(&["aoc", "2022", "16.txt"]).unwrap();
@@ -80,25 +80,21 @@ impl Map {
             .collect();
 
         fn moves_from<'a>(start: &'a ValveId, map: &'a Map) -> Vec<Move<'a>> {
+            let distances = dijkstra_all(start, |&from| {
+                map.get(from).unwrap().tunnels_to.iter().map(|target| Step {
+                    to: target,
+                    additional_cost: 1,
+                })
+            });
+
             map.valves
                 .iter()
                 .filter_map(|(id, data)| if data.flow > 0 { Some(id) } else { None })
                 .filter_map(|target| {
-                    astar(
-                        start,
-                        |&from| {
-                            map.get(from).unwrap().tunnels_to.iter().map(|target| Step {
-                                to: target,
-                                additional_cost: 1,
-                            })
-                        },
-                        |&valve| valve == target,
-                        |_valve| 0,
-                    )
-                })
-                .map(|path| Move {
-                    target: path.nodes.last().unwrap(),
-                    cost: path.cost + 1,
+                    distances.get(target).map(|&(cost, _)| Move {
+                        target,
+                        cost: cost + 1,
+                    })
                 })
                 .collect()
         }
@@ -58,6 +58,7 @@ impl FromStr for Map {
 mod part_1 {
     use crate::Map;
     use anyhow::Result;
+    use challenges_common::graph::Neighbourhood;
 
     pub fn run(map: &Map) -> Result<u32> {
         Ok(map.parts_numbers_sum())
@@ -74,7 +75,7 @@ mod part_1 {
                 .filter_map(|(number, positions)| {
                     let is_part_number = positions
                         .iter()
-                        .flat_map(|position| position.neighbours(true))
+                        .flat_map(|position| position.neighbours(&Neighbourhood::Moore))
                         .any(|position| matches!(self.grid.get(&position), Some(&c) if !c.is_ascii_digit() && c != '.'));
 
                     if is_part_number {
@@ -118,6 +119,7 @@ mod part_1 {
 mod part_2 {
     use crate::Map;
     use anyhow::Result;
+    use challenges_common::graph::Neighbourhood;
     use itertools::Itertools;
 
     pub fn run(map: &Map) -> Result<u32> {
@@ -134,7 +136,7 @@ mod part_2 {
                 .coords()
                 .filter(|coord| self.grid.get(coord) == Some(&'*'))
                 .filter_map(|coord| {
-                    let gear_neighbours_coord = coord.neighbours(true).collect_vec();
+                    let gear_neighbours_coord = coord.neighbours(&Neighbourhood::Moore).collect_vec();
 
                     let numbers = self
                         .numbers
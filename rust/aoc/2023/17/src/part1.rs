@@ -22,7 +22,7 @@ pub(crate) fn run(content: &str) -> Result<Res> {
     Ok(path.cost)
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
 struct PathElement {
     coord: Coord,
     direction: Direction,
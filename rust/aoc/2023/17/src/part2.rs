@@ -21,7 +21,7 @@ pub(crate) fn run(content: &str) -> anyhow::Result<Res> {
     Ok(path.cost)
 }
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Hash, PartialEq, Eq, Clone)]
 struct PathElement {
     coord: Coord,
     direction: Direction,
@@ -1,5 +1,6 @@
-use crate::{Page, PageOrderingRule, PageOrderingRules, Update, Updates};
+use crate::{PageOrderingRules, Update, Updates};
 use anyhow::*;
+use challenges_common::graph::topo::sort_by_rules;
 use challenges_common::MyIterTools;
 use itertools::Itertools;
 
@@ -13,52 +14,35 @@ pub(crate) fn run(content: &str) -> Result<Res> {
     let rules: PageOrderingRules = rules.join("\n").parse()?;
     let mut updates: Updates = updates.join("\n").parse()?;
 
-    Ok(updates
+    updates
         .updates
         .iter_mut()
         .filter(|update| !rules.satisfies(update))
-        .update(|update| update.sort(&rules))
-        .map(|update| update.middle_page())
-        .sum())
-}
-
-trait Part2Rules {
-    fn get_rule(&self, page1: Page, page2: Page) -> Option<&PageOrderingRule>;
-}
-
-impl Part2Rules for PageOrderingRules {
-    fn get_rule(&self, page1: Page, page2: Page) -> Option<&PageOrderingRule> {
-        self.rules.iter().find(|rule| {
-            (rule.before == page1 && rule.after == page2)
-                || (rule.before == page2 && rule.after == page1)
+        .map(|update| {
+            update.sort(&rules)?;
+            Ok(update.middle_page())
         })
-    }
+        .sum()
 }
 
 trait SortByRules {
-    fn sort(&mut self, rules: &PageOrderingRules);
+    fn sort(&mut self, rules: &PageOrderingRules) -> Result<()>;
 }
 
 impl SortByRules for Updates {
-    fn sort(&mut self, rules: &PageOrderingRules) {
+    fn sort(&mut self, rules: &PageOrderingRules) -> Result<()> {
         for update in self.updates.iter_mut() {
-            update.sort(rules)
+            update.sort(rules)?;
         }
+        Ok(())
     }
 }
 
 impl SortByRules for Update {
-    fn sort(&mut self, rules: &PageOrderingRules) {
-        self.pages.sort_by(|a, b| match rules.get_rule(*a, *b) {
-            None => std::cmp::Ordering::Equal,
-            Some(rule) => {
-                if rule.before == *a && rule.after == *b {
-                    std::cmp::Ordering::Less
-                } else {
-                    std::cmp::Ordering::Greater
-                }
-            }
-        });
+    fn sort(&mut self, rules: &PageOrderingRules) -> Result<()> {
+        let rules = rules.rules.iter().map(|rule| (rule.before, rule.after));
+        self.pages = sort_by_rules(&self.pages, rules)?;
+        Ok(())
     }
 }
 
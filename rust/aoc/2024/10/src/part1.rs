@@ -1,5 +1,5 @@
 use anyhow::*;
-use challenges_common::graph::{CannotParseElementFromChar, Coord, Grid};
+use challenges_common::graph::{CannotParseElementFromChar, Coord, Grid, Neighbourhood};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -73,7 +73,7 @@ impl FromStr for Map {
             .map(|coord| {
                 let target_height = grid.get(&coord).unwrap().0 + 1;
                 let targets = coord
-                    .neighbours(false)
+                    .neighbours(&Neighbourhood::VonNeumann)
                     .filter(|neighbour| grid.get(neighbour) == Some(&Height(target_height)))
                     .collect_vec();
                 (coord, targets)
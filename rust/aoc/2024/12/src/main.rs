@@ -1,6 +1,6 @@
 use anyhow::Error;
 use challenges_common::graph;
-use challenges_common::graph::Grid;
+use challenges_common::graph::{Grid, Neighbourhood};
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -42,7 +42,7 @@ impl Regions {
     // this is trash performance wise (quick-win should use square to limit possibilities)...
     fn add_cell(&mut self, plant: Plant, coord: Coord) {
         let regions = self.regions.remove(&plant).unwrap_or_default();
-        let neighbors = coord.neighbours(false).collect_vec();
+        let neighbors = coord.neighbours(&Neighbourhood::VonNeumann).collect_vec();
         let mut groups = regions.into_iter().into_group_map_by(|region| {
             region
                 .cells
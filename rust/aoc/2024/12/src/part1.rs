@@ -1,4 +1,5 @@
 use crate::Price;
+use challenges_common::graph::Neighbourhood;
 
 pub trait Region {
     fn price(&self) -> Price;
@@ -14,7 +15,7 @@ impl Region for crate::Region {
         self.cells
             .iter()
             .map(|&cell| {
-                cell.neighbours(false)
+                cell.neighbours(&Neighbourhood::VonNeumann)
                     .filter(|neighbor| !self.cells.contains(neighbor))
                     .count()
             })
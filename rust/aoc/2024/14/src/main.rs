@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Error};
+use challenges_common::geom::Rect;
 use challenges_common::graph::grid;
 use itertools::Itertools;
 use regex::Regex;
@@ -29,18 +30,23 @@ impl Map {
     }
 
     fn safety_factor(&self, robots: &Robots) -> usize {
-        let mid_x = self.width / 2;
-        let mid_y = self.height / 2;
-        let robots_by_quarter = robots.robots.iter().into_group_map_by(|robot| {
-            (robot.position.x.cmp(&mid_x), robot.position.y.cmp(&mid_y))
-        });
-
-        use std::cmp::Ordering::*;
-        robots_by_quarter
+        let area = Rect::new(
+            Coord { x: 0, y: 0 },
+            Coord {
+                x: self.width - 1,
+                y: self.height - 1,
+            },
+        );
+
+        area.quadrants()
+            .unwrap()
             .iter()
-            .flat_map(|(cmps, robots)| match cmps {
-                (Equal, _) | (_, Equal) => None,
-                _ => Some(robots.len()),
+            .map(|quadrant| {
+                robots
+                    .robots
+                    .iter()
+                    .filter(|robot| quadrant.contains(&robot.position))
+                    .count()
             })
             .product()
     }
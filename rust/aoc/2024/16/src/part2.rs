@@ -1,6 +1,6 @@
 use crate::{Map, Reindeer};
 use anyhow::*;
-use challenges_common::graph::astar_multiple;
+use challenges_common::graph::{astar_multiple, Paths};
 use std::collections::HashSet;
 
 type Res = usize;
@@ -10,8 +10,7 @@ pub(crate) fn run(content: &str) -> Result<Res> {
     let path_nodes = reindeer
         .get_best_paths(&map)
         .ok_or_else(|| anyhow!("no path found"))?
-        .0
-        .into_iter()
+        .nodes()
         .map(|reindeer| reindeer.coord)
         .collect::<HashSet<_>>();
 
@@ -19,11 +18,11 @@ pub(crate) fn run(content: &str) -> Result<Res> {
 }
 
 trait Part2Reindeer {
-    fn get_best_paths(&self, map: &Map) -> Option<(HashSet<Reindeer>, usize)>;
+    fn get_best_paths(&self, map: &Map) -> Option<Paths<Reindeer, usize>>;
 }
 
 impl Part2Reindeer for Reindeer {
-    fn get_best_paths(&self, map: &Map) -> Option<(HashSet<Reindeer>, usize)> {
+    fn get_best_paths(&self, map: &Map) -> Option<Paths<Reindeer, usize>> {
         astar_multiple(
             self.clone(),
             |reindeer| reindeer.next(map),
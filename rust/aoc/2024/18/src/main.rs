@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Error};
 use challenges_common::graph;
-use challenges_common::graph::{astar, Path, Step};
+use challenges_common::graph::{astar, Neighbourhood, Path, Step};
 use itertools::Itertools;
 use std::collections::HashSet;
 use std::str::FromStr;
@@ -33,7 +33,7 @@ impl Map {
             Coord { x: 0, y: 0 },
             |coord| {
                 coord
-                    .neighbours(false)
+                    .neighbours(&Neighbourhood::VonNeumann)
                     .filter(|coord| self.contains(coord) && !self.falled.contains(coord))
                     .map(|coord| Step {
                         to: coord,
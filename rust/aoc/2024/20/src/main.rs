@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Error};
-use challenges_common::graph::{astar, grid, CannotParseElementFromChar, Step};
+use challenges_common::graph::{astar, grid, CannotParseElementFromChar, Neighbourhood, Step};
 use std::ops::Deref;
 use std::str::FromStr;
 
@@ -29,7 +29,7 @@ impl Map {
             self.start,
             |coord| {
                 coord
-                    .neighbours(false)
+                    .neighbours(&Neighbourhood::VonNeumann)
                     .filter(|coord| self.get(coord) == Some(&Block::Empty))
                     .map(|coord| Step {
                         to: coord,
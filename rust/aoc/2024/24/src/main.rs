@@ -1,7 +1,7 @@
 use anyhow::{anyhow, bail, Error};
+use challenges_common::graph::topo;
 use challenges_common::MyIterTools;
 use itertools::Itertools;
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::str::FromStr;
@@ -19,7 +19,7 @@ type Name = String;
 type Value = bool;
 
 struct Device {
-    values: RefCell<HashMap<String, Value>>,
+    init_values: HashMap<String, Value>,
     outputs: HashMap<String, Gate>,
 }
 
@@ -31,21 +31,19 @@ impl Device {
             .reduce(|_gate, _, gate| panic!("Multiple gates for the same output"));
 
         Self {
-            values: RefCell::new(init_values),
+            init_values,
             outputs,
         }
     }
 
-    fn get_value(&self, name: &Name) -> Option<Value> {
-        let found_value = { self.values.borrow().get(name).cloned() };
-        match found_value {
-            Some(value) => Some(value),
-            None => {
-                let value = self.outputs.get(name)?.eval(self)?;
-                self.values.borrow_mut().insert(name.clone(), value);
-                Some(value)
-            }
-        }
+    /// Value of every wire the init values lead to.
+    fn evaluate(&self) -> Result<HashMap<Name, Value>, topo::Cycle<Name>> {
+        topo::evaluate(
+            &self.outputs,
+            self.init_values.clone(),
+            |gate| [gate.in1.clone(), gate.in2.clone()],
+            |gate, inputs| gate.op.apply(*inputs[0], *inputs[1]),
+        )
     }
 }
 
@@ -79,19 +77,6 @@ impl Gate {
         &self.out
     }
 
-    fn eval(&self, device: &Device) -> Option<Value> {
-        let a = device.get_value(&self.in1)?;
-        let b = device.get_value(&self.in2)?;
-
-        let res = match &self.op {
-            GateOp::And => a && b,
-            GateOp::Or => a || b,
-            GateOp::Xor => a != b,
-        };
-
-        Some(res)
-    }
-
     fn other_input(&self, in_name: &str) -> &Name {
         if self.in1 == in_name {
             &self.in2
@@ -119,6 +104,16 @@ enum GateOp {
     Xor,
 }
 
+impl GateOp {
+    fn apply(&self, a: Value, b: Value) -> Value {
+        match self {
+            GateOp::And => a && b,
+            GateOp::Or => a || b,
+            GateOp::Xor => a != b,
+        }
+    }
+}
+
 impl FromStr for Device {
     type Err = Error;
 
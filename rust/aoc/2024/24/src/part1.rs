@@ -7,21 +7,22 @@ use std::str::FromStr;
 type Res = u64;
 pub(crate) fn run(content: &str) -> Result<Res> {
     let device: Device = content.parse()?;
-    Ok(device.get_z_output())
+    device.get_z_output()
 }
 
 trait Part2Device {
-    fn get_z_output(&self) -> Res;
+    fn get_z_output(&self) -> Result<Res>;
 }
 
 impl Part2Device for Device {
-    fn get_z_output(&self) -> Res {
+    fn get_z_output(&self) -> Result<Res> {
+        let values = self.evaluate()?;
         let mut res = 0;
 
         for i in 0.. {
             let name = format!("z{:02}", i);
 
-            let Some(value) = self.get_value(&name) else {
+            let Some(&value) = values.get(&name) else {
                 break;
             };
 
@@ -30,7 +31,7 @@ impl Part2Device for Device {
             }
         }
 
-        res
+        Ok(res)
     }
 }
 
@@ -0,0 +1,110 @@
+//! To compare with another revision, run
+//! `cargo bench -p challenges_common --bench astar -- --save-baseline before`
+//! on it, then `cargo bench -p challenges_common --bench astar -- --baseline before`
+//! on this one.
+
+use challenges_common::get_input_content;
+use challenges_common::graph::{astar, astar_multiple, Coord, Direction, Grid, Step, Turn};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+/// 2024/16: moving costs 1, turning 1000.
+fn reindeer_maze(c: &mut Criterion) {
+    let maze: Grid<char> = get_input_content(&["aoc", "2024", "16.txt"])
+        .parse()
+        .unwrap();
+    let start = (maze.find(|&c| c == 'S').unwrap(), Direction::Right);
+    let end = maze.find(|&c| c == 'E').unwrap();
+
+    let next = |&(coord, direction): &(Coord, Direction)| {
+        let forward = coord
+            .try_at(direction)
+            .filter(|to| maze.get(to).is_some_and(|&c| c != '#'))
+            .map(|to| Step {
+                to: (to, direction),
+                additional_cost: 1,
+            });
+        let turns = Turn::all().map(|turn| Step {
+            to: (coord, direction.turn(turn)),
+            additional_cost: 1000,
+        });
+        forward.into_iter().chain(turns)
+    };
+    let heuristic = |(coord, _): &(Coord, Direction)| coord.manhattan_dist_to(&end);
+
+    c.bench_function("astar 2024/16", |b| {
+        b.iter(|| {
+            astar(
+                black_box(start),
+                next,
+                |(coord, _)| *coord == end,
+                heuristic,
+            )
+        })
+    });
+    c.bench_function("astar_multiple 2024/16", |b| {
+        b.iter(|| {
+            astar_multiple(
+                black_box(start),
+                next,
+                |(coord, _)| *coord == end,
+                heuristic,
+            )
+        })
+    });
+}
+
+/// 2022/24: crossing the valley between the blizzards.
+fn blizzard_basin(c: &mut Criterion) {
+    let valley: Grid<char> = get_input_content(&["aoc", "2022", "24.txt"])
+        .parse()
+        .unwrap();
+    let (width, height) = (valley.width() - 2, valley.height() - 2);
+    let start = Coord { x: 1, y: 0 };
+    let end = Coord {
+        x: width,
+        y: height + 1,
+    };
+
+    let is_free = |coord: &Coord, minute: usize| {
+        if *coord == start || *coord == end {
+            return true;
+        }
+        if coord.x == 0 || coord.y == 0 || coord.x > width || coord.y > height {
+            return false;
+        }
+        let (x, y) = (coord.x - 1, coord.y - 1);
+        let at = |x: usize, y: usize| valley.get(&Coord { x: x + 1, y: y + 1 }).unwrap();
+        *at((x + width - minute % width) % width, y) != '>'
+            && *at((x + minute) % width, y) != '<'
+            && *at(x, (y + height - minute % height) % height) != 'v'
+            && *at(x, (y + minute) % height) != '^'
+    };
+
+    let next = |&(coord, minute): &(Coord, usize)| {
+        Direction::all()
+            .into_iter()
+            .filter_map(move |direction| coord.try_at(direction))
+            .chain([coord])
+            .filter(move |to| is_free(to, minute + 1))
+            .map(move |to| Step {
+                to: (to, minute + 1),
+                additional_cost: 1,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    c.bench_function("astar 2022/24", |b| {
+        b.iter(|| {
+            astar(
+                black_box((start, 0)),
+                next,
+                |(coord, _)| *coord == end,
+                |(coord, _)| coord.manhattan_dist_to(&end),
+            )
+        })
+    });
+}
+
+criterion_group!(benches, reindeer_maze, blizzard_basin);
+criterion_main!(benches);
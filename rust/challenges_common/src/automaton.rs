@@ -0,0 +1,262 @@
+use crate::cycle::forecast_state;
+use crate::graph::{Grid, Neighbourhood, SparseGrid, Vec2};
+use num_traits::PrimInt;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Cellular automaton: every generation, each cell becomes
+/// `rule(cell, neighbours)`, the neighbours being taken from the previous
+/// generation according to `neighbourhood`.
+pub struct Automaton<S: Space, R> {
+    state: S,
+    buffer: S,
+    neighbourhood: Neighbourhood,
+    rule: R,
+    generation: usize,
+}
+
+impl<S, R> Automaton<S, R>
+where
+    S: Space,
+    R: FnMut(&S::Node, &[&S::Node]) -> S::Node,
+{
+    pub fn new(state: S, neighbourhood: Neighbourhood, rule: R) -> Self {
+        Self {
+            buffer: state.clone(),
+            state,
+            neighbourhood,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Number of steps done so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation, returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let changed = self
+            .state
+            .step_into(&mut self.buffer, &self.neighbourhood, &mut self.rule);
+        std::mem::swap(&mut self.state, &mut self.buffer);
+        self.generation += 1;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until nothing changes anymore, returns the generation of the
+    /// stable state.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        self.generation - 1
+    }
+
+    /// State at `generation`, counted from the current one, jumping ahead once
+    /// a cycle is found.
+    pub fn forecast(self, generation: usize) -> S
+    where
+        S: Eq + Debug,
+    {
+        let Self {
+            state,
+            neighbourhood,
+            mut rule,
+            ..
+        } = self;
+
+        forecast_state(
+            state,
+            |state| {
+                let mut next = state.clone();
+                state.step_into(&mut next, &neighbourhood, &mut rule);
+                Some(next)
+            },
+            generation,
+        )
+    }
+}
+
+/// Storage an [`Automaton`] can step.
+pub trait Space: Clone {
+    type Node: PartialEq;
+
+    /// Writes the next generation into `next`, which holds a previous
+    /// generation, and returns whether any cell changed.
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighbourhood: &Neighbourhood,
+        rule: &mut impl FnMut(&Self::Node, &[&Self::Node]) -> Self::Node,
+    ) -> bool;
+}
+
+/// Cells outside of the grid are not neighbours, the topology is followed.
+impl<N, U> Space for Grid<N, U>
+where
+    N: Clone + PartialEq,
+    U: PrimInt,
+{
+    type Node = N;
+
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighbourhood: &Neighbourhood,
+        rule: &mut impl FnMut(&N, &[&N]) -> N,
+    ) -> bool {
+        let mut changed = false;
+        let mut neighbours = Vec::with_capacity(neighbourhood.offsets().len());
+        for (coord, node) in self.entries() {
+            neighbours.clear();
+            neighbours.extend(self.neighbours(&coord, neighbourhood).map(|(_, n)| n));
+
+            let new = rule(node, &neighbours);
+            changed |= &new != node;
+            *next.get_mut(&coord).unwrap() = new;
+        }
+        changed
+    }
+}
+
+/// Only cells around stored ones are computed: the rule must keep a default
+/// cell surrounded by default cells to the default.
+impl<N, U> Space for SparseGrid<N, U>
+where
+    N: Clone + PartialEq,
+    U: PrimInt + Hash,
+{
+    type Node = N;
+
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighbourhood: &Neighbourhood,
+        rule: &mut impl FnMut(&N, &[&N]) -> N,
+    ) -> bool {
+        let reversed: Vec<_> = neighbourhood
+            .offsets()
+            .iter()
+            .map(|offset| Vec2::new(-offset.x, -offset.y))
+            .collect();
+        let candidates: HashSet<_> = self
+            .coords()
+            .flat_map(|coord| {
+                reversed
+                    .iter()
+                    .filter_map(move |offset| coord.try_at_offset(offset))
+                    .chain([coord])
+            })
+            .collect();
+
+        next.clear();
+        let mut changed = false;
+        let mut neighbours = Vec::with_capacity(neighbourhood.offsets().len());
+        for coord in candidates {
+            neighbours.clear();
+            neighbours.extend(self.neighbours(&coord, neighbourhood).map(|(_, n)| n));
+
+            let node = self.get(&coord);
+            let new = rule(node, &neighbours);
+            changed |= &new != node;
+            if &new != next.default_value() {
+                next.insert(coord, new);
+            }
+        }
+        changed
+    }
+}
+
+/// Conway's game of life rule, for cells being alive or not.
+pub fn life(alive: &bool, neighbours: &[&bool]) -> bool {
+    matches!(
+        (alive, neighbours.iter().filter(|&&&n| n).count()),
+        (true, 2) | (_, 3)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Coord;
+
+    fn glider() -> SparseGrid<bool> {
+        let mut grid = SparseGrid::new(false);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.insert(Coord { x, y }, true);
+        }
+        grid
+    }
+
+    #[test]
+    fn blinker_on_a_grid() {
+        let grid: Grid<bool> = Grid::from([
+            [false, false, false],
+            [true, true, true],
+            [false, false, false],
+        ]);
+        let mut automaton = Automaton::new(grid.clone(), Neighbourhood::Moore, life);
+
+        assert!(automaton.step());
+        assert_eq!(
+            automaton.state(),
+            &Grid::from([
+                [false, true, false],
+                [false, true, false],
+                [false, true, false],
+            ])
+        );
+        assert!(automaton.step());
+        assert_eq!(automaton.state(), &grid);
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn still_life_is_stable() {
+        let block: Grid<bool> = Grid::from([[true, true], [true, true]]);
+        let mut automaton = Automaton::new(block, Neighbourhood::Moore, life);
+        assert_eq!(automaton.run_until_stable(), 0);
+    }
+
+    #[test]
+    fn glider_moves_on_a_sparse_grid() {
+        let mut automaton = Automaton::new(glider(), Neighbourhood::Moore, life);
+        automaton.run(4);
+
+        let moved: HashSet<_> = automaton.state().coords().collect();
+        let expected: HashSet<_> = glider()
+            .coords()
+            .map(|coord| Coord {
+                x: coord.x + 1,
+                y: coord.y + 1,
+            })
+            .collect();
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn forecast_far_generation() {
+        let grid: Grid<bool> = Grid::from([
+            [false, true, false],
+            [false, true, false],
+            [false, true, false],
+        ]);
+        let automaton = Automaton::new(grid.clone(), Neighbourhood::Moore, life);
+        assert_eq!(automaton.forecast(1_000_000_000), grid);
+    }
+}
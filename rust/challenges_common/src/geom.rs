@@ -1,6 +1,7 @@
 use crate::graph::Coord;
 use itertools::Itertools;
 use num_traits::{PrimInt, Signed};
+use std::iter::successors;
 
 pub struct Polygon<U = usize> {
     vertices: Vec<Point<U>>,
@@ -51,6 +52,141 @@ impl<U> From<Point<U>> for Coord<U> {
     }
 }
 
+/// Axis-aligned rectangle, both corners inclusive.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Rect<U = usize> {
+    pub min: Coord<U>,
+    pub max: Coord<U>,
+}
+
+impl<U> Rect<U>
+where
+    U: PrimInt,
+{
+    /// Panics when `min` is past `max` on either axis.
+    pub fn new(min: Coord<U>, max: Coord<U>) -> Self {
+        assert!(
+            min.x <= max.x && min.y <= max.y,
+            "Rect min corner must not be past its max corner"
+        );
+        Self { min, max }
+    }
+
+    /// Rectangle of the single cell `coord`.
+    pub fn point(coord: Coord<U>) -> Self {
+        Self {
+            min: coord,
+            max: coord,
+        }
+    }
+
+    /// Bounding box of `coords`, `None` when there is none.
+    pub fn from_coords(coords: impl IntoIterator<Item = Coord<U>>) -> Option<Self> {
+        coords.into_iter().fold(None, |rect, coord| {
+            Some(match rect {
+                None => Self::point(coord),
+                Some(rect) => rect.extended_to(&coord),
+            })
+        })
+    }
+
+    /// Smallest rectangle containing this one and `coord`.
+    pub fn extended_to(&self, coord: &Coord<U>) -> Self {
+        self.union(&Self::point(*coord))
+    }
+
+    pub fn width(&self) -> U {
+        self.max.x - self.min.x + U::one()
+    }
+
+    pub fn height(&self) -> U {
+        self.max.y - self.min.y + U::one()
+    }
+
+    pub fn area(&self) -> U {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, coord: &Coord<U>) -> bool {
+        (self.min.x..=self.max.x).contains(&coord.x) && (self.min.y..=self.max.y).contains(&coord.y)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Coord {
+            x: self.min.x.max(other.min.x),
+            y: self.min.y.max(other.min.y),
+        };
+        let max = Coord {
+            x: self.max.x.min(other.max.x),
+            y: self.max.y.min(other.max.y),
+        };
+        (min.x <= max.x && min.y <= max.y).then_some(Self { min, max })
+    }
+
+    /// Smallest rectangle containing both.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Coord {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+            },
+            max: Coord {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+            },
+        }
+    }
+
+    /// Grows every side by `margin`, saturating at the limits of `U`.
+    pub fn expand(&self, margin: U) -> Self {
+        Self {
+            min: Coord {
+                x: self.min.x.saturating_sub(margin),
+                y: self.min.y.saturating_sub(margin),
+            },
+            max: Coord {
+                x: self.max.x.saturating_add(margin),
+                y: self.max.y.saturating_add(margin),
+            },
+        }
+    }
+
+    /// Contained coords in row-major order.
+    pub fn coords(&self) -> impl Iterator<Item = Coord<U>> {
+        let Self { min, max } = *self;
+        let range =
+            move |from: U, to: U| successors(Some(from), move |&v| (v < to).then(|| v + U::one()));
+        range(min.y, max.y).flat_map(move |y| range(min.x, max.x).map(move |x| Coord { x, y }))
+    }
+
+    /// Top-left, top-right, bottom-left and bottom-right quarters. With an odd
+    /// width (or height) the centre column (or row) belongs to none of them.
+    ///
+    /// `None` when the rectangle is too thin to be split.
+    pub fn quadrants(&self) -> Option<[Self; 4]> {
+        let two = U::one() + U::one();
+        let (half_width, half_height) = (self.width() / two, self.height() / two);
+        if half_width.is_zero() || half_height.is_zero() {
+            return None;
+        }
+
+        let left = (self.min.x, self.min.x + half_width - U::one());
+        let right = (self.max.x - half_width + U::one(), self.max.x);
+        let top = (self.min.y, self.min.y + half_height - U::one());
+        let bottom = (self.max.y - half_height + U::one(), self.max.y);
+        let quadrant = |(min_x, max_x): (U, U), (min_y, max_y): (U, U)| {
+            Self::new(Coord { x: min_x, y: min_y }, Coord { x: max_x, y: max_y })
+        };
+
+        Some([
+            quadrant(left, top),
+            quadrant(right, top),
+            quadrant(left, bottom),
+            quadrant(right, bottom),
+        ])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +216,79 @@ mod tests {
 
         assert_eq!(square.area::<i8>(), 12);
     }
+
+    fn rect(min: (i32, i32), max: (i32, i32)) -> Rect<i32> {
+        Rect::new(Coord { x: min.0, y: min.1 }, Coord { x: max.0, y: max.1 })
+    }
+
+    #[test]
+    fn bounding_box() {
+        let coords = [
+            Coord { x: 3, y: -1 },
+            Coord { x: -2, y: 4 },
+            Coord { x: 0, y: 0 },
+        ];
+        let bounds = Rect::from_coords(coords).unwrap();
+
+        assert_eq!(bounds, rect((-2, -1), (3, 4)));
+        assert_eq!(bounds.area(), 36);
+        assert!(coords.iter().all(|coord| bounds.contains(coord)));
+        assert!(!bounds.contains(&Coord { x: 4, y: 0 }));
+        assert_eq!(Rect::<i32>::from_coords([]), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_rejects_inverted_corners() {
+        rect((3, 0), (1, 2));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = rect((0, 0), (4, 2));
+        let b = rect((3, 1), (6, 5));
+
+        assert_eq!(a.intersection(&b), Some(rect((3, 1), (4, 2))));
+        assert_eq!(a.union(&b), rect((0, 0), (6, 5)));
+        assert_eq!(a.intersection(&rect((5, 0), (6, 1))), None);
+    }
+
+    #[test]
+    fn expand_saturates() {
+        let rect = Rect::new(Coord { x: 1usize, y: 3 }, Coord { x: 2, y: 4 });
+        assert_eq!(
+            rect.expand(2),
+            Rect::new(Coord { x: 0, y: 1 }, Coord { x: 4, y: 6 })
+        );
+    }
+
+    #[test]
+    fn coords_are_row_major() {
+        let coords: Vec<_> = rect((1, 0), (2, 1)).coords().collect();
+        assert_eq!(
+            coords,
+            vec![
+                Coord { x: 1, y: 0 },
+                Coord { x: 2, y: 0 },
+                Coord { x: 1, y: 1 },
+                Coord { x: 2, y: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn quadrants_skip_the_centre_lines() {
+        let [top_left, top_right, bottom_left, bottom_right] =
+            rect((0, 0), (10, 6)).quadrants().unwrap();
+
+        assert_eq!(top_left, rect((0, 0), (4, 2)));
+        assert_eq!(top_right, rect((6, 0), (10, 2)));
+        assert_eq!(bottom_left, rect((0, 4), (4, 6)));
+        assert_eq!(bottom_right, rect((6, 4), (10, 6)));
+        assert_eq!(
+            rect((0, 0), (3, 3)).quadrants().unwrap()[3],
+            rect((2, 2), (3, 3))
+        );
+        assert_eq!(rect((0, 0), (0, 3)).quadrants(), None);
+    }
 }
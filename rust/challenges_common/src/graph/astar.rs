@@ -1,126 +1,491 @@
 use core::cmp::Reverse;
-use std::fmt::Debug;
 use std::{
-    collections::{BinaryHeap, HashMap},
+    collections::{BinaryHeap, HashMap, HashSet},
     hash::Hash,
     ops::Add,
-    rc::Rc,
+    time::Instant,
 };
 
+mod limits;
 mod multiple;
-pub use multiple::astar_multiple;
+mod observer;
+pub use limits::{SearchLimits, SearchOutcome};
+pub use multiple::{astar_multiple, astar_multiple_with_limits, Paths};
+pub use observer::{Callbacks, SearchObserver, SearchStats};
 
 pub fn astar<N, C, Nexts>(
     starting_at: N,
-    mut next: impl FnMut(&N) -> Nexts,
+    next: impl FnMut(&N) -> Nexts,
     is_end: impl Fn(&N) -> bool,
     heuristic: impl Fn(&N) -> C,
 ) -> Option<Path<N, C>>
 where
-    N: Node,
+    N: Node + Clone,
     C: Cost,
     Nexts: IntoIterator<Item = Step<N, C>>,
 {
-    let mut queue: Queue<N, C> = BinaryHeap::new();
-    let mut optimals: Optimals<N, C> = HashMap::new();
-
-    let info = NodeInfo::start(starting_at, &heuristic);
-    add_node(&mut queue, &mut optimals, info);
-
-    while let Some(Reverse(from)) = queue.pop() {
-        if is_optimal(&optimals, &from) {
-            let reached = &from.node;
-
-            if is_end(reached) {
-                return Some(rebuild_path(from, queue, optimals));
-            } else {
-                for next_step in next(reached) {
-                    let info = node_info(
-                        from.clone(),
-                        Rc::new(next_step.to),
-                        next_step.additional_cost,
-                        &optimals,
-                        &heuristic,
-                    );
-                    add_node(&mut queue, &mut optimals, info);
-                }
+    astar_from_many([(starting_at, C::default())], next, is_end, heuristic)
+}
+
+/// [`astar`] starting from all `starts` at once, each with its initial cost.
+pub fn astar_from_many<N, C, Nexts>(
+    starts: impl IntoIterator<Item = (N, C)>,
+    next: impl FnMut(&N) -> Nexts,
+    is_end: impl Fn(&N) -> bool,
+    heuristic: impl Fn(&N) -> C,
+) -> Option<Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    astar_from_many_with_limits(starts, next, is_end, heuristic, SearchLimits::default()).found()
+}
+
+/// [`astar`] reporting what the search went through, and calling `observer`
+/// on every expansion and push.
+pub fn astar_with_observer<N, C, Nexts>(
+    starting_at: N,
+    mut next: impl FnMut(&N) -> Nexts,
+    is_end: impl Fn(&N) -> bool,
+    heuristic: impl Fn(&N) -> C,
+    observer: impl SearchObserver<N, C>,
+) -> (Option<Path<N, C>>, SearchStats)
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    let mut search = Search::observed([(starting_at, C::default())], &heuristic, Some(observer));
+    let stop = search.run(
+        &mut next,
+        &heuristic,
+        |search, from| Visit::stop_if(is_end(&search.infos[from].node)),
+        &SearchLimits::default(),
+    );
+
+    let stats = search.refreshed_stats();
+    match stop {
+        Stop::End(end) => (Some(search.into_path(end)), stats),
+        _ => (None, stats),
+    }
+}
+
+/// [`astar`] giving up once one of the `limits` is reached.
+pub fn astar_with_limits<N, C, Nexts>(
+    starting_at: N,
+    next: impl FnMut(&N) -> Nexts,
+    is_end: impl Fn(&N) -> bool,
+    heuristic: impl Fn(&N) -> C,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    astar_from_many_with_limits(
+        [(starting_at, C::default())],
+        next,
+        is_end,
+        heuristic,
+        limits,
+    )
+}
+
+/// [`astar_from_many`] giving up once one of the `limits` is reached.
+pub fn astar_from_many_with_limits<N, C, Nexts>(
+    starts: impl IntoIterator<Item = (N, C)>,
+    mut next: impl FnMut(&N) -> Nexts,
+    is_end: impl Fn(&N) -> bool,
+    heuristic: impl Fn(&N) -> C,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    let mut search = Search::new(starts, &heuristic);
+    match search.run(
+        &mut next,
+        &heuristic,
+        |search, from| Visit::stop_if(is_end(&search.infos[from].node)),
+        &limits,
+    ) {
+        Stop::End(end) => SearchOutcome::Found(search.into_path(end)),
+        Stop::Exhausted => SearchOutcome::Exhausted,
+        Stop::Limit(closest) => SearchOutcome::LimitReached {
+            best_so_far: search.into_path(closest),
+        },
+    }
+}
+
+/// Best path to each of the reachable `goals`, in a single search.
+///
+/// A goal is recorded the first time it is expanded, so `heuristic` must be
+/// consistent (never dropping by more than the cost of a step) for that path
+/// to be its best, e.g. the minimum of consistent estimates to each goal not
+/// found yet.
+pub fn astar_to_goals<N, C, Nexts>(
+    starts: impl IntoIterator<Item = (N, C)>,
+    next: impl FnMut(&N) -> Nexts,
+    goals: impl IntoIterator<Item = N>,
+    heuristic: impl Fn(&N) -> C,
+) -> HashMap<N, Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    match astar_to_goals_with_limits(starts, next, goals, heuristic, SearchLimits::default()) {
+        SearchOutcome::Found(found) | SearchOutcome::LimitReached { best_so_far: found } => found,
+        SearchOutcome::Exhausted => HashMap::new(),
+    }
+}
+
+/// [`astar_to_goals`] giving up once one of the `limits` is reached, with the
+/// goals found so far.
+///
+/// The paths are `Found` once every goal is reached or the search runs out of
+/// nodes: it is never `Exhausted`.
+pub fn astar_to_goals_with_limits<N, C, Nexts>(
+    starts: impl IntoIterator<Item = (N, C)>,
+    mut next: impl FnMut(&N) -> Nexts,
+    goals: impl IntoIterator<Item = N>,
+    heuristic: impl Fn(&N) -> C,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<HashMap<N, Path<N, C>>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    let mut remaining: HashSet<N> = goals.into_iter().collect();
+    let mut found = HashMap::new();
+
+    let mut search = Search::new(starts, &heuristic);
+    let stop = search.run(
+        &mut next,
+        &heuristic,
+        |search, from| {
+            let node = &search.infos[from].node;
+            if remaining.remove(node) {
+                found.insert(node.clone(), search.path(from));
             }
+            Visit::stop_if(remaining.is_empty())
+        },
+        &limits,
+    );
+
+    match stop {
+        Stop::Limit(_) => SearchOutcome::LimitReached { best_so_far: found },
+        _ => SearchOutcome::Found(found),
+    }
+}
+
+/// What [`Search::run`] does with the node it pops.
+pub(super) enum Visit {
+    Expand,
+    /// Neither expands the node nor stops.
+    Skip,
+    Stop,
+}
+
+impl Visit {
+    pub(super) fn stop_if(stop: bool) -> Self {
+        if stop {
+            Visit::Stop
+        } else {
+            Visit::Expand
         }
     }
+}
 
-    return None;
+/// How [`Search::run`] stopped.
+pub(super) enum Stop {
+    /// `visit` stopped on this node.
+    End(usize),
+    Exhausted,
+    /// A limit was reached, this node being the explored one the heuristic
+    /// deems closest to an end.
+    Limit(usize),
+}
 
-    type Queue<N, C> = BinaryHeap<Reverse<Rc<NodeInfo<N, C>>>>;
-    type Optimals<N, C> = HashMap<Rc<N>, Rc<NodeInfo<N, C>>>;
+/// Nodes are stored once in an arena, the queue and the ancestors referring
+/// to them by index.
+pub(super) struct Search<N, C, O> {
+    infos: Vec<NodeInfo<N, C>>,
+    indexes: HashMap<N, usize>,
+    /// `(score, cost, index)`: entries whose cost is no longer the node's one
+    /// are outdated.
+    queue: BinaryHeap<Reverse<(C, C, usize)>>,
+    /// Ancestors other than `previous_ancestor` reaching a node for the same
+    /// cost, only kept when looking for every optimal path.
+    ties: Option<HashMap<usize, Vec<usize>>>,
+    stats: SearchStats,
+    started: Instant,
+    /// `None` when nobody observes, to skip refreshing `stats.elapsed`.
+    observer: Option<O>,
+}
 
-    fn is_optimal<N: Node, C: Cost>(optimals: &Optimals<N, C>, info: &Rc<NodeInfo<N, C>>) -> bool {
-        match optimals.get(&info.node) {
-            Some(existing) => info.cost <= existing.cost,
-            None => true,
-        }
+impl<N: Node + Clone, C: Cost> Search<N, C, ()> {
+    pub(super) fn new(
+        starts: impl IntoIterator<Item = (N, C)>,
+        heuristic: &impl Fn(&N) -> C,
+    ) -> Self {
+        Self::observed(starts, heuristic, None)
     }
+}
 
-    fn is_improvement<N: Node, C: Cost>(optimals: &Optimals<N, C>, info: &NodeInfo<N, C>) -> bool {
-        match optimals.get(&info.node) {
-            Some(existing) => info.cost < existing.cost,
-            None => true,
+impl<N: Node + Clone, C: Cost, O: SearchObserver<N, C>> Search<N, C, O> {
+    fn observed(
+        starts: impl IntoIterator<Item = (N, C)>,
+        heuristic: &impl Fn(&N) -> C,
+        observer: Option<O>,
+    ) -> Self {
+        let mut search = Self {
+            infos: Vec::new(),
+            indexes: HashMap::new(),
+            queue: BinaryHeap::new(),
+            ties: None,
+            stats: SearchStats::default(),
+            started: Instant::now(),
+            observer,
+        };
+        for (start, cost) in starts {
+            search.reach(start, cost, None, heuristic);
         }
+        search
     }
 
-    fn add_node<N, C>(queue: &mut Queue<N, C>, optimals: &mut Optimals<N, C>, info: NodeInfo<N, C>)
+    /// Records every ancestor reaching a node for its best cost.
+    fn keeping_ties(mut self) -> Self {
+        self.ties = Some(HashMap::new());
+        self
+    }
+
+    /// Pops nodes, best score first, and expands them until `visit` stops on
+    /// one of them, none is left or one of the `limits` is reached.
+    pub(super) fn run<Nexts>(
+        &mut self,
+        next: &mut impl FnMut(&N) -> Nexts,
+        heuristic: &impl Fn(&N) -> C,
+        mut visit: impl FnMut(&Self, usize) -> Visit,
+        limits: &SearchLimits<C>,
+    ) -> Stop
     where
-        N: Node,
-        C: Cost,
+        Nexts: IntoIterator<Item = Step<N, C>>,
     {
-        let info = Rc::new(info);
-        if is_improvement(optimals, &info) {
-            queue.push(Reverse(info.clone()));
-            optimals.insert(Rc::clone(&info.node), info);
+        let closeness = |info: &NodeInfo<N, C>| (info.heuristic, info.cost);
+        let mut closest: Option<usize> = None;
+        while let Some(from) = self.pop() {
+            match visit(self, from) {
+                Visit::Expand => {}
+                Visit::Skip => continue,
+                Visit::Stop => return Stop::End(from),
+            }
+
+            let info = &self.infos[from];
+            let closest_yet = match closest {
+                Some(closest) if closeness(&self.infos[closest]) <= closeness(info) => closest,
+                _ => from,
+            };
+            closest = Some(closest_yet);
+            if limits.reached(info.score(), self.stats.expanded) {
+                return Stop::Limit(closest_yet);
+            }
+
+            self.expand(from, next, heuristic);
         }
+
+        Stop::Exhausted
     }
 
-    fn rebuild_path<N: Node, C: Cost>(
-        from: Rc<NodeInfo<N, C>>,
-        queue: Queue<N, C>,
-        optimals: Optimals<N, C>,
-    ) -> Path<N, C> {
-        drop(queue);
-        drop(optimals);
-
-        let cost = from.cost;
-        let mut nodes = Vec::new();
-        let mut next_node_info = Some(unwrap_rc_or_panic(from));
-        while let Some(current_node_info) = next_node_info {
-            nodes.push(unwrap_rc_or_panic(current_node_info.node));
-            next_node_info = current_node_info.previous_ancestor.map(unwrap_rc_or_panic);
+    /// Next node to explore, skipping the ones reached since for a lower cost.
+    fn pop(&mut self) -> Option<usize> {
+        while let Some(Reverse((_, cost, index))) = self.queue.pop() {
+            if self.infos[index].cost == cost {
+                return Some(index);
+            }
         }
+        None
+    }
 
-        nodes.reverse();
+    fn expand<Nexts>(
+        &mut self,
+        from: usize,
+        next: &mut impl FnMut(&N) -> Nexts,
+        heuristic: &impl Fn(&N) -> C,
+    ) where
+        Nexts: IntoIterator<Item = Step<N, C>>,
+    {
+        let info = &mut self.infos[from];
+        info.expanded = true;
+        let cost = info.cost;
+        self.stats.expanded += 1;
+        if let Some(observer) = &mut self.observer {
+            self.stats.elapsed = self.started.elapsed();
+            observer.on_expand(&info.node, cost, &self.stats);
+        }
 
-        Path { nodes, cost }
+        for Step {
+            to,
+            additional_cost,
+        } in next(&self.infos[from].node)
+        {
+            self.stats.generated += 1;
+            self.reach(to, cost + additional_cost, Some(from), heuristic);
+        }
     }
 
-    fn node_info<N, C>(
-        from: Rc<NodeInfo<N, C>>,
-        to: Rc<N>,
-        additional_cost: C,
-        optimals: &Optimals<N, C>,
-        heuristic: impl Fn(&N) -> C,
-    ) -> NodeInfo<N, C>
-    where
-        N: Node,
-        C: Cost,
-    {
-        let heuristic = match optimals.get(&to) {
-            Some(existing) => existing.heuristic,
-            _ => heuristic(&to),
+    /// Records `node` if it is new or reached for a lower cost.
+    fn reach(
+        &mut self,
+        node: N,
+        cost: C,
+        previous_ancestor: Option<usize>,
+        heuristic: &impl Fn(&N) -> C,
+    ) {
+        let index = match self.indexes.get(&node) {
+            Some(&index) => {
+                if cost == self.infos[index].cost {
+                    if let Some(from) = previous_ancestor {
+                        self.tie(index, from);
+                    }
+                }
+                let info = &mut self.infos[index];
+                if cost >= info.cost {
+                    return;
+                }
+                if info.expanded {
+                    info.expanded = false;
+                    self.stats.reopened += 1;
+                }
+                if let Some(ties) = &mut self.ties {
+                    ties.remove(&index);
+                }
+                info.cost = cost;
+                info.previous_ancestor = previous_ancestor;
+                index
+            }
+            None => {
+                let index = self.infos.len();
+                let heuristic = heuristic(&node);
+                self.indexes.insert(node.clone(), index);
+                self.infos.push(NodeInfo {
+                    node,
+                    previous_ancestor,
+                    cost,
+                    heuristic,
+                    expanded: false,
+                });
+                index
+            }
         };
+        let info = &self.infos[index];
+        self.queue.push(Reverse((info.score(), info.cost, index)));
+        self.stats.peak_queue = self.stats.peak_queue.max(self.queue.len());
+        if let Some(observer) = &mut self.observer {
+            self.stats.elapsed = self.started.elapsed();
+            observer.on_push(&info.node, info.cost, info.score());
+        }
+    }
+
+    /// Every expanded node, with its cost and the node it is reached from.
+    pub(super) fn into_settled(self) -> HashMap<N, (C, Option<N>)> {
+        let previous: Vec<_> = self
+            .infos
+            .iter()
+            .map(|info| {
+                info.previous_ancestor
+                    .map(|previous| self.infos[previous].node.clone())
+            })
+            .collect();
+        self.infos
+            .into_iter()
+            .zip(previous)
+            .filter(|(info, _)| info.expanded)
+            .map(|(info, previous)| (info.node, (info.cost, previous)))
+            .collect()
+    }
+
+    /// Records `from` as one more ancestor of `index`, reached for the same
+    /// cost, unless `from` is reached through `index`: through a cycle of
+    /// zero cost steps.
+    fn tie(&mut self, index: usize, from: usize) {
+        if self.ties.is_none() || self.ancestors(index).any(|ancestor| ancestor == from) {
+            return;
+        }
+
+        // only ancestors of `from` at its cost can be reached through `index`
+        let cost = self.infos[from].cost;
+        let mut to_visit = vec![from];
+        let mut visited = HashSet::from([from]);
+        while let Some(descendant) = to_visit.pop() {
+            if descendant == index {
+                return;
+            }
+            for ancestor in self.ancestors(descendant) {
+                if self.infos[ancestor].cost == cost && visited.insert(ancestor) {
+                    to_visit.push(ancestor);
+                }
+            }
+        }
+
+        if let Some(ties) = &mut self.ties {
+            ties.entry(index).or_default().push(from);
+        }
+    }
+
+    /// Every recorded ancestor reaching `index` for its best cost.
+    fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let ties = self.ties.as_ref().and_then(|ties| ties.get(&index));
+        self.infos[index]
+            .previous_ancestor
+            .into_iter()
+            .chain(ties.into_iter().flatten().copied())
+    }
+
+    /// Stats with the time elapsed since the search started.
+    fn refreshed_stats(&mut self) -> SearchStats {
+        self.stats.elapsed = self.started.elapsed();
+        self.stats
+    }
+
+    /// Indexes of the nodes leading to `to`, `to` included, from the start.
+    fn path_indexes(&self, to: usize) -> Vec<usize> {
+        let mut indexes = vec![to];
+        while let Some(previous) = self.infos[*indexes.last().unwrap()].previous_ancestor {
+            indexes.push(previous);
+        }
+        indexes.reverse();
+        indexes
+    }
+
+    /// Path leading to `to`, leaving the search untouched.
+    fn path(&self, to: usize) -> Path<N, C> {
+        Path {
+            nodes: self
+                .path_indexes(to)
+                .into_iter()
+                .map(|index| self.infos[index].node.clone())
+                .collect(),
+            cost: self.infos[to].cost,
+        }
+    }
 
-        NodeInfo {
-            node: to,
-            cost: from.cost + additional_cost,
-            previous_ancestor: Some(from),
-            heuristic,
+    fn into_path(self, to: usize) -> Path<N, C> {
+        let indexes = self.path_indexes(to);
+        let cost = self.infos[to].cost;
+        let mut nodes: Vec<_> = self.infos.into_iter().map(|info| Some(info.node)).collect();
+
+        Path {
+            nodes: indexes
+                .into_iter()
+                .map(|index| nodes[index].take().unwrap())
+                .collect(),
+            cost,
         }
     }
 }
@@ -143,56 +508,25 @@ pub trait Node: Hash + Eq {}
 impl<T: Ord + Copy + Add<Output = Self> + Sized + Default> Cost for T {}
 impl<T: Hash + Eq> Node for T {}
 
-#[derive(PartialEq, Eq, Debug)]
-struct NodeInfo<N, C>
-where
-    N: Node,
-    C: Cost,
-{
-    node: Rc<N>,
-    previous_ancestor: Option<Rc<NodeInfo<N, C>>>,
+#[derive(Debug)]
+struct NodeInfo<N, C> {
+    node: N,
+    previous_ancestor: Option<usize>,
     cost: C,
     heuristic: C,
+    expanded: bool,
 }
 
-impl<N: Node, C: Cost> NodeInfo<N, C> {
-    fn start(start: N, heuristic: &dyn Fn(&N) -> C) -> Self {
-        let heuristic = heuristic(&start);
-        Self {
-            node: Rc::new(start),
-            previous_ancestor: None,
-            cost: C::default(),
-            heuristic,
-        }
-    }
-
+impl<N, C: Cost> NodeInfo<N, C> {
     fn score(&self) -> C {
         self.cost + self.heuristic
     }
 }
 
-impl<N: Node, C: Cost> PartialOrd for NodeInfo<N, C> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl<N: Node, C: Cost> Ord for NodeInfo<N, C> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.score().cmp(&other.score())
-    }
-}
-
-fn unwrap_rc_or_panic<T>(rc: Rc<T>) -> T {
-    match Rc::try_unwrap(rc) {
-        Ok(t) => t,
-        Err(_) => panic!("trying to unwrap a non single RC"),
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::iter::once;
+    use std::time::Duration;
 
     use super::*;
 
@@ -303,4 +637,145 @@ mod test {
         assert_eq!(path.cost, 30);
         assert_eq!(path.nodes, vec![0, 1, 3]);
     }
+
+    #[test]
+    fn from_many_starts_with_initial_costs() {
+        let graph = CustomGraph {
+            edges: vec![(0, 2, 10), (1, 2, 1), (2, 3, 1)],
+        };
+
+        let path =
+            astar_from_many([(0, 0), (1, 5)], |&n| graph.next(n), |&n| n == 3, |_| 0).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes, vec![1, 2, 3]);
+
+        let path =
+            astar_from_many([(0, 0), (1, 20)], |&n| graph.next(n), |&n| n == 3, |_| 0).unwrap();
+        assert_eq!(path.nodes, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn to_every_goal_in_one_search() {
+        let graph = CustomGraph {
+            edges: vec![(0, 1, 10), (1, 3, 20), (0, 2, 20), (2, 3, 5), (3, 4, 1)],
+        };
+
+        let paths = astar_to_goals([(0, 0)], |&n| graph.next(n), [1, 4, 5], |_| 0);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[&1].nodes, vec![0, 1]);
+        assert_eq!(paths[&4].cost, 26);
+        assert_eq!(paths[&4].nodes, vec![0, 2, 3, 4]);
+
+        let limits = SearchLimits {
+            max_cost: Some(15),
+            ..Default::default()
+        };
+        let SearchOutcome::LimitReached { best_so_far } =
+            astar_to_goals_with_limits([(0, 0)], |&n| graph.next(n), [1, 4], |_| 0, limits)
+        else {
+            panic!("expected the cost limit to be reached")
+        };
+        assert_eq!(best_so_far.keys().collect::<Vec<_>>(), vec![&1]);
+    }
+
+    #[test]
+    fn observer_sees_reopened_nodes() {
+        let graph = CustomGraph {
+            edges: vec![(0, 1, 5), (0, 2, 1), (2, 1, 1), (1, 3, 100)],
+        };
+        let mut expanded = Vec::new();
+        let mut pushes = 0;
+
+        let (path, stats) = astar_with_observer(
+            0,
+            |&n| graph.next(n),
+            |&n| n == 3,
+            |&n| if n == 2 { 10 } else { 0 },
+            Callbacks {
+                on_expand: |&node: &u8, cost: u8, _: &SearchStats| expanded.push((node, cost)),
+                on_push: |_: &u8, _, _| pushes += 1,
+            },
+        );
+
+        let path = path.unwrap();
+        assert_eq!((path.nodes, path.cost), (vec![0, 2, 1, 3], 102));
+        assert_eq!(expanded, vec![(0, 0), (1, 5), (2, 1), (1, 2)]);
+        assert_eq!(pushes, 6);
+        assert_eq!(
+            (
+                stats.expanded,
+                stats.generated,
+                stats.reopened,
+                stats.peak_queue
+            ),
+            (4, 5, 1, 2)
+        );
+    }
+
+    #[test]
+    fn observer_sees_elapsed_time() {
+        let graph = CustomGraph::same_weights(vec![(0, 1), (1, 2)]);
+        let mut elapsed = Vec::new();
+
+        let (_, stats) = astar_with_observer(
+            0,
+            |&n| graph.next(n),
+            |&n| n == 2,
+            |_| 0,
+            Callbacks {
+                on_expand: |_: &u8, _: u8, stats: &SearchStats| {
+                    elapsed.push(stats.elapsed);
+                    std::thread::sleep(Duration::from_millis(1));
+                },
+                on_push: |_: &u8, _, _| {},
+            },
+        );
+
+        assert_eq!(elapsed.len(), 2);
+        assert!(elapsed[1] >= Duration::from_millis(1));
+        assert!(stats.elapsed >= elapsed[1]);
+    }
+
+    #[test]
+    fn stops_on_limits_with_the_closest_path() {
+        let graph = CustomGraph::same_weights(vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+        let search =
+            |limits| astar_with_limits(0, |&n| graph.next(n), |&n| n == 5, |&n| 5 - n, limits);
+
+        let found = search(SearchLimits::default()).found().unwrap();
+        assert_eq!(found.cost, 5);
+
+        let SearchOutcome::LimitReached { best_so_far } = search(SearchLimits {
+            max_expansions: Some(2),
+            ..Default::default()
+        }) else {
+            panic!("expected the expansions limit to be reached")
+        };
+        assert_eq!((best_so_far.nodes, best_so_far.cost), (vec![0, 1, 2], 2));
+
+        assert!(matches!(
+            search(SearchLimits {
+                max_cost: Some(4),
+                ..Default::default()
+            }),
+            SearchOutcome::LimitReached { .. }
+        ));
+        assert!(matches!(
+            search(SearchLimits {
+                deadline: Some(Instant::now()),
+                ..Default::default()
+            }),
+            SearchOutcome::LimitReached { .. }
+        ));
+        assert!(matches!(
+            astar_with_limits(
+                0,
+                |&n| graph.next(n),
+                |&n| n == 9,
+                |_| 0,
+                SearchLimits::default()
+            ),
+            SearchOutcome::Exhausted
+        ));
+    }
 }
@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+/// When to give up a search, see [`super::astar_with_limits`] and the other
+/// `_with_limits` searches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits<C> {
+    /// Stops once every remaining path would cost more, according to the
+    /// heuristic.
+    pub max_cost: Option<C>,
+    pub max_expansions: Option<usize>,
+    pub deadline: Option<Instant>,
+}
+
+impl<C> Default for SearchLimits<C> {
+    fn default() -> Self {
+        Self {
+            max_cost: None,
+            max_expansions: None,
+            deadline: None,
+        }
+    }
+}
+
+impl<C: Ord> SearchLimits<C> {
+    /// Whether to stop before expanding a node, `score` being a lower bound
+    /// of the cost of any path still to be found.
+    pub(crate) fn reached(&self, score: C, expansions: usize) -> bool {
+        self.max_cost
+            .as_ref()
+            .is_some_and(|max_cost| score > *max_cost)
+            || self
+                .max_expansions
+                .is_some_and(|max_expansions| expansions >= max_expansions)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// How a search given [`SearchLimits`] ended.
+pub enum SearchOutcome<T> {
+    /// What the search looked for, e.g. the [`crate::graph::Path`] to an end.
+    Found(T),
+    /// Every reachable node was explored without finding an end.
+    Exhausted,
+    /// The partial result closest to what was looked for, e.g. the path to the
+    /// explored node the heuristic deems closest to an end.
+    LimitReached { best_so_far: T },
+}
+
+impl<T> SearchOutcome<T> {
+    pub fn found(self) -> Option<T> {
+        match self {
+            SearchOutcome::Found(found) => Some(found),
+            _ => None,
+        }
+    }
+}
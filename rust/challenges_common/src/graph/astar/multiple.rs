@@ -1,227 +1,126 @@
-use crate::graph::astar::unwrap_rc_or_panic;
+use super::{Search, SearchLimits, SearchObserver, SearchOutcome, Stop, Visit};
 use crate::graph::{Cost, Node, Step};
-use std::cell::RefCell;
-use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
-use std::rc::Rc;
+use std::collections::{HashMap, VecDeque};
 
-// mod paths;
+mod paths;
 
+pub use paths::Paths;
+
+/// Like [`crate::graph::astar`], but keeps every path of optimal cost.
 pub fn astar_multiple<N, C, Nexts>(
     starting_at: N,
-    mut next: impl FnMut(&N) -> Nexts,
+    next: impl FnMut(&N) -> Nexts,
     is_end: impl Fn(&N) -> bool,
     heuristic: impl Fn(&N) -> C,
-) -> Option<(HashSet<N>, C)>
+) -> Option<Paths<N, C>>
 where
-    N: Node,
+    N: Node + Clone,
     C: Cost,
     Nexts: IntoIterator<Item = Step<N, C>>,
 {
-    let mut queue: Queue<N, C> = BinaryHeap::new();
-    let mut optimals: Optimals<N, C> = HashMap::new();
-    let mut ends = Vec::new();
-    {
-        let mut ends_costs = None;
-
-        let info = AggregatedNodeInfo::start(starting_at, &heuristic);
-        add_node(&mut queue, &mut optimals, info);
-
-        while let Some(Reverse(from)) = queue.pop() {
-            if let Some(ends_costs) = ends_costs {
-                if from.borrow().score() > ends_costs {
-                    break;
-                }
-            }
-
-            if is_optimal(&optimals, &from, ends_costs) {
-                // usefull clone ?
-                let reached = { &from.borrow().node.clone() };
-
-                if is_end(reached) {
-                    ends_costs = Some(from.borrow().cost);
-                    ends.push(from.clone());
-                } else {
-                    for next_step in next(reached) {
-                        let info = node_info(
-                            from.clone(),
-                            Rc::new(next_step.to),
-                            next_step.additional_cost,
-                            &optimals,
-                            &heuristic,
-                        );
-                        add_node(&mut queue, &mut optimals, info);
-                    }
-                }
-            }
-        }
-    }
-
-    return rebuild_paths(ends, queue, optimals);
-
-    type Queue<N, C> = BinaryHeap<Reverse<Rc<RefCell<AggregatedNodeInfo<N, C>>>>>;
-    type Optimals<N, C> = HashMap<Rc<N>, Rc<RefCell<AggregatedNodeInfo<N, C>>>>;
-
-    fn is_optimal<N: Node, C: Cost>(
-        optimals: &Optimals<N, C>,
-        info: &Rc<RefCell<AggregatedNodeInfo<N, C>>>,
-        ends_cost: Option<C>,
-    ) -> bool {
-        if let Some(ends_cost) = ends_cost {
-            if info.borrow().score() > ends_cost {
-                return false;
-            }
-        }
-
-        match optimals.get(&info.borrow().node) {
-            Some(existing) => info.borrow().cost <= existing.borrow().cost,
-            None => true,
-        }
-    }
-
-    fn add_node<N, C>(
-        queue: &mut Queue<N, C>,
-        optimals: &mut Optimals<N, C>,
-        info: AggregatedNodeInfo<N, C>,
-    ) where
-        N: Node,
-        C: Cost,
-    {
-        let to_add = match optimals.get(&info.node) {
-            None => {
-                let info = Rc::new(RefCell::new(info));
-                let node = Rc::clone(&info.as_ref().borrow().node);
-                Some((node, info))
-            }
-            Some(existing) => {
-                let ordering = { info.cost.cmp(&existing.borrow().cost) };
-                match ordering {
-                    Ordering::Less => {
-                        let info = Rc::new(RefCell::new(info));
-                        let node = Rc::clone(&info.borrow().node);
-                        Some((node, info))
-                    }
-                    Ordering::Equal => {
-                        if let (Some(existing_previous_ancestors), Some(info_previous_ancestors)) = (
-                            &mut existing.borrow_mut().previous_ancestors,
-                            &info.previous_ancestors,
-                        ) {
-                            existing_previous_ancestors
-                                .extend(info_previous_ancestors.iter().cloned());
-                        };
-                        None
-                    }
-                    Ordering::Greater => None,
-                }
-            }
-        };
-
-        if let Some((node, info)) = to_add {
-            queue.push(Reverse(Rc::clone(&info)));
-            optimals.insert(node, info);
-        }
-    }
-
-    fn rebuild_paths<N: Node, C: Cost>(
-        ends: Vec<Rc<RefCell<AggregatedNodeInfo<N, C>>>>,
-        queue: Queue<N, C>,
-        optimals: Optimals<N, C>,
-    ) -> Option<(HashSet<N>, C)> {
-        drop(queue);
-        drop(optimals);
-
-        if ends.is_empty() {
-            return None;
-        }
-
-        let mut nodes = HashSet::new();
-        let cost = { ends[0].borrow().cost };
-
-        for end in ends {
-            let mut queue = VecDeque::new();
-            queue.push_back(end);
-
-            while let Some(end) = queue.pop_front() {
-                nodes.insert(end.borrow().node.clone());
-
-                if let Some(previous_ancestors) = &end.borrow().previous_ancestors {
-                    for node_info in previous_ancestors {
-                        queue.push_back(node_info.clone());
-                    }
-                }
-            }
-        }
-
-        Some((nodes.into_iter().map(unwrap_rc_or_panic).collect(), cost))
-    }
-
-    fn node_info<N, C>(
-        from: Rc<RefCell<AggregatedNodeInfo<N, C>>>,
-        to: Rc<N>,
-        additional_cost: C,
-        optimals: &Optimals<N, C>,
-        heuristic: impl Fn(&N) -> C,
-    ) -> AggregatedNodeInfo<N, C>
-    where
-        N: Node,
-        C: Cost,
-    {
-        let heuristic = match optimals.get(&to) {
-            Some(existing) => existing.borrow().heuristic,
-            _ => heuristic(&to),
-        };
-
-        AggregatedNodeInfo {
-            node: to,
-            cost: from.borrow().cost + additional_cost,
-            previous_ancestors: Some(vec![from.clone()]),
-            heuristic,
-        }
-    }
+    astar_multiple_with_limits(
+        starting_at,
+        next,
+        is_end,
+        heuristic,
+        SearchLimits::default(),
+    )
+    .found()
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct AggregatedNodeInfo<N, C>
+/// [`astar_multiple`] giving up once one of the `limits` is reached. The
+/// best so far are then the optimal paths to the ends found yet, if any, or
+/// to the explored node the heuristic deems closest to an end.
+pub fn astar_multiple_with_limits<N, C, Nexts>(
+    starting_at: N,
+    mut next: impl FnMut(&N) -> Nexts,
+    is_end: impl Fn(&N) -> bool,
+    heuristic: impl Fn(&N) -> C,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<Paths<N, C>>
 where
-    N: Node,
+    N: Node + Clone,
     C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
 {
-    node: Rc<N>,
-    previous_ancestors: Option<Vec<Rc<RefCell<AggregatedNodeInfo<N, C>>>>>, // remove refcell
-    cost: C,
-    heuristic: C,
-}
-
-impl<N: Node, C: Cost> AggregatedNodeInfo<N, C> {
-    fn start(start: N, heuristic: &dyn Fn(&N) -> C) -> Self {
-        let heuristic = heuristic(&start);
-        Self {
-            node: Rc::new(start),
-            previous_ancestors: None,
-            cost: C::default(),
-            heuristic,
-        }
-    }
-
-    fn score(&self) -> C {
-        self.cost + self.heuristic
+    let mut search = Search::new([(starting_at, C::default())], &heuristic).keeping_ties();
+    let mut ends = Vec::new();
+    let mut ends_cost = None;
+
+    let stop = search.run(
+        &mut next,
+        &heuristic,
+        |search, from| {
+            let info = &search.infos[from];
+            if ends_cost.is_some_and(|ends_cost| info.score() > ends_cost) {
+                Visit::Stop
+            } else if is_end(&info.node) {
+                ends_cost = Some(info.cost);
+                ends.push(from);
+                Visit::Skip
+            } else {
+                Visit::Expand
+            }
+        },
+        &limits,
+    );
+
+    match stop {
+        Stop::Limit(closest) => SearchOutcome::LimitReached {
+            best_so_far: if ends.is_empty() {
+                search.into_paths(vec![closest])
+            } else {
+                search.into_paths(ends)
+            },
+        },
+        _ if ends.is_empty() => SearchOutcome::Exhausted,
+        _ => SearchOutcome::Found(search.into_paths(ends)),
     }
 }
 
-impl<N: Node, C: Cost> PartialOrd for AggregatedNodeInfo<N, C> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+impl<N: Node + Clone, C: Cost, O: SearchObserver<N, C>> Search<N, C, O> {
+    /// Keeps only the nodes leading to the non-empty `ends`, indexed in the
+    /// order they are found going back from the ends.
+    fn into_paths(self, ends: Vec<usize>) -> Paths<N, C> {
+        let cost = self.infos[ends[0]].cost;
+
+        let mut paths_indexes: HashMap<usize, usize> = ends
+            .iter()
+            .enumerate()
+            .map(|(paths_index, &index)| (index, paths_index))
+            .collect();
+        let mut kept = ends.clone();
+        let mut to_visit: VecDeque<_> = ends.iter().copied().collect();
+        let mut predecessors = Vec::new();
+        while let Some(index) = to_visit.pop_front() {
+            let previous = self
+                .ancestors(index)
+                .map(|ancestor| {
+                    *paths_indexes.entry(ancestor).or_insert_with(|| {
+                        kept.push(ancestor);
+                        to_visit.push_back(ancestor);
+                        kept.len() - 1
+                    })
+                })
+                .collect();
+            predecessors.push(previous);
+        }
 
-impl<N: Node, C: Cost> Ord for AggregatedNodeInfo<N, C> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.score().cmp(&other.score())
+        let mut nodes: Vec<_> = self.infos.into_iter().map(|info| Some(info.node)).collect();
+        Paths::new(
+            kept.into_iter()
+                .map(|index| nodes[index].take().unwrap())
+                .collect(),
+            predecessors,
+            (0..ends.len()).collect(),
+            cost,
+        )
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
     use std::iter::once;
 
     use super::*;
@@ -247,7 +146,7 @@ mod test {
                 })
         }
 
-        fn path(&self, from: u8, to: u8) -> Option<(HashSet<u8>, u8)> {
+        fn path(&self, from: u8, to: u8) -> Option<Paths<u8, u8>> {
             astar_multiple(from, |&n| self.next(n), |&n| n == to, |_| 0)
         }
     }
@@ -260,7 +159,7 @@ mod test {
 
     #[test]
     fn with_1_edge() {
-        let (nodes, cost) = astar_multiple(
+        let paths = astar_multiple(
             0,
             |_| {
                 once(Step {
@@ -273,36 +172,131 @@ mod test {
         )
         .unwrap();
 
-        assert_eq!(cost, 1);
-        assert_eq!(nodes, { HashSet::from_iter(vec![0, 1]) });
+        assert_eq!(paths.cost(), 1);
+        let mut nodes = paths.into_nodes();
+        nodes.sort();
+        assert_eq!(nodes, vec![0, 1]);
     }
 
     #[test]
     fn with_1_edge_with_graph() {
-        let (nodes, cost) = CustomGraph::same_weights(vec![(0, 1)]).path(0, 1).unwrap();
+        let paths = CustomGraph::same_weights(vec![(0, 1)]).path(0, 1).unwrap();
 
-        assert_eq!(cost, 1);
-        assert_eq!(nodes, HashSet::from_iter(vec![0, 1]));
+        assert_eq!(paths.cost(), 1);
+        assert_eq!(
+            paths.nodes().collect::<HashSet<_>>(),
+            HashSet::from([&0, &1])
+        );
     }
 
     #[test]
     fn with_5_edges() {
-        let (nodes, cost) = CustomGraph::same_weights(vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)])
+        let paths = CustomGraph::same_weights(vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)])
             .path(0, 5)
             .unwrap();
 
-        assert_eq!(cost, 5);
-        assert_eq!(nodes, HashSet::from_iter(vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(paths.cost(), 5);
+        assert_eq!(paths.count(), 1);
+        let path = paths.iter().next().unwrap();
+        assert_eq!((path.nodes, path.cost), (vec![0, 1, 2, 3, 4, 5], 5));
     }
 
     #[test]
     fn with_3_edges_and_2_paths() {
-        let (nodes, cost) = CustomGraph::same_weights(vec![(0, 1), (0, 2), (2, 3), (1, 3)])
+        let paths = CustomGraph::same_weights(vec![(0, 1), (0, 2), (2, 3), (1, 3)])
             .path(0, 3)
             .unwrap();
 
-        assert_eq!(cost, 2);
-        assert_eq!(nodes, HashSet::from_iter(vec![0, 1, 2, 3]));
+        assert_eq!(paths.cost(), 2);
+        assert_eq!(
+            paths.nodes().collect::<HashSet<_>>(),
+            HashSet::from([&0, &1, &2, &3])
+        );
+        assert_eq!(paths.count(), 2);
+        assert_eq!(paths.edges().count(), 4);
+    }
+
+    #[test]
+    fn enumerates_every_optimal_path() {
+        // 2 ways from 0 to 3, then 2 ways from 3 to 6, 2 to 4 and 5 being too long
+        let paths = CustomGraph {
+            edges: vec![
+                (0, 1, 1),
+                (0, 2, 1),
+                (1, 3, 1),
+                (2, 3, 1),
+                (3, 4, 1),
+                (3, 5, 1),
+                (4, 6, 1),
+                (5, 6, 1),
+                (0, 6, 5),
+                (2, 6, 4),
+            ],
+        }
+        .path(0, 6)
+        .unwrap();
+
+        assert_eq!(paths.cost(), 4);
+        assert_eq!(paths.count(), 4);
+        assert_eq!(paths.count_through(&1), 2);
+        assert_eq!(paths.count_through(&3), 4);
+        assert_eq!(paths.count_through(&7), 0);
+
+        let mut all: Vec<_> = paths.iter().map(|path| path.nodes).collect();
+        all.sort();
+        assert_eq!(
+            all,
+            vec![
+                vec![0, 1, 3, 4, 6],
+                vec![0, 1, 3, 5, 6],
+                vec![0, 2, 3, 4, 6],
+                vec![0, 2, 3, 5, 6],
+            ]
+        );
+        assert_eq!(paths.iter().take(1).count(), 1);
+    }
+
+    #[test]
+    fn stops_on_limits_with_the_paths_to_the_closest_node() {
+        let graph = CustomGraph::same_weights(vec![(0, 1), (0, 2), (2, 3), (1, 3), (3, 4)]);
+        let search = |max_expansions| {
+            astar_multiple_with_limits(
+                0,
+                |&n| graph.next(n),
+                |&n| n == 4,
+                |&n| [3, 2, 2, 1, 0][n as usize],
+                SearchLimits {
+                    max_expansions: Some(max_expansions),
+                    ..Default::default()
+                },
+            )
+        };
+
+        let SearchOutcome::LimitReached { best_so_far } = search(3) else {
+            panic!("expected the expansions limit to be reached")
+        };
+        assert_eq!((best_so_far.cost(), best_so_far.count()), (2, 2));
+        assert_eq!(best_so_far.ends().collect::<Vec<_>>(), vec![&3]);
+
+        let SearchOutcome::Found(paths) = search(4) else {
+            panic!("expected the paths to be found")
+        };
+        assert_eq!((paths.cost(), paths.count()), (3, 2));
+    }
+
+    #[test]
+    fn zero_cost_cycles_are_not_followed() {
+        let paths = CustomGraph {
+            edges: vec![(0, 1, 1), (1, 2, 0), (2, 1, 0), (2, 3, 1), (1, 3, 1)],
+        }
+        .path(0, 3)
+        .unwrap();
+
+        assert_eq!(paths.cost(), 2);
+        assert_eq!(paths.count(), 2);
+        let mut all: Vec<_> = paths.iter().map(|path| path.nodes).collect();
+        all.sort();
+        assert_eq!(all, vec![vec![0, 1, 2, 3], vec![0, 1, 3]]);
     }
 
     #[test]
@@ -1,20 +1,161 @@
-use std::collections::HashSet;
-use std::hash::Hash;
+use crate::graph::Path;
 
-pub struct Pathes<N> {
-    nodes: HashSet<N>,
+/// Every optimal path found by [`super::astar_multiple`], stored as the
+/// shortest-path DAG: each node knows the nodes it is optimally reached from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths<N, C> {
+    nodes: Vec<N>,
+    predecessors: Vec<Vec<usize>>,
+    ends: Vec<usize>,
+    cost: C,
+    /// Node indexes, each one after all its predecessors.
+    order: Vec<usize>,
 }
 
-impl<N: Hash + Eq> Pathes<N> {
-    pub fn new(nodes: HashSet<N>) -> Self {
-        Self { nodes }
+impl<N, C: Copy> Paths<N, C> {
+    /// `predecessors[i]` are the indexes of the nodes from which `nodes[i]`
+    /// is reached, `ends` the indexes of the reached ends.
+    pub(super) fn new(
+        nodes: Vec<N>,
+        predecessors: Vec<Vec<usize>>,
+        ends: Vec<usize>,
+        cost: C,
+    ) -> Self {
+        let order = topological_order(&predecessors);
+        Self {
+            nodes,
+            predecessors,
+            ends,
+            cost,
+            order,
+        }
     }
 
-    pub fn nodes(&self) -> &HashSet<N> {
-        &self.nodes
+    pub fn cost(&self) -> C {
+        self.cost
     }
 
-    pub fn add(&mut self, node: N) {
-        self.nodes.insert(node);
+    /// Every node on at least one optimal path.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> + '_ {
+        self.nodes.iter()
     }
+
+    pub fn into_nodes(self) -> Vec<N> {
+        self.nodes
+    }
+
+    pub fn ends(&self) -> impl Iterator<Item = &N> + '_ {
+        self.ends.iter().map(|&end| &self.nodes[end])
+    }
+
+    /// Edges `(from, to)` of the shortest-path DAG.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> + '_ {
+        self.predecessors
+            .iter()
+            .enumerate()
+            .flat_map(move |(to, froms)| {
+                froms
+                    .iter()
+                    .map(move |&from| (&self.nodes[from], &self.nodes[to]))
+            })
+    }
+
+    /// Number of distinct optimal paths.
+    pub fn count(&self) -> usize {
+        let from_start = self.counts_from_start();
+        self.ends.iter().map(|&end| from_start[end]).sum()
+    }
+
+    /// Number of distinct optimal paths going through `node`.
+    pub fn count_through(&self, node: &N) -> usize
+    where
+        N: PartialEq,
+    {
+        let Some(index) = self.nodes.iter().position(|n| n == node) else {
+            return 0;
+        };
+        self.counts_from_start()[index] * self.counts_to_ends()[index]
+    }
+
+    /// Lazily enumerates every optimal path.
+    pub fn iter(&self) -> impl Iterator<Item = Path<N, C>> + '_
+    where
+        N: Clone,
+    {
+        // partial paths, from an end back towards the start
+        let mut stack: Vec<Vec<usize>> = self.ends.iter().map(|&end| vec![end]).collect();
+        std::iter::from_fn(move || {
+            while let Some(partial) = stack.pop() {
+                let predecessors = &self.predecessors[*partial.last().unwrap()];
+                if predecessors.is_empty() {
+                    return Some(Path {
+                        nodes: partial
+                            .iter()
+                            .rev()
+                            .map(|&i| self.nodes[i].clone())
+                            .collect(),
+                        cost: self.cost,
+                    });
+                }
+
+                for &predecessor in predecessors.iter().rev() {
+                    let mut longer = partial.clone();
+                    longer.push(predecessor);
+                    stack.push(longer);
+                }
+            }
+            None
+        })
+    }
+
+    fn counts_from_start(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.nodes.len()];
+        for &node in &self.order {
+            let predecessors = &self.predecessors[node];
+            counts[node] = if predecessors.is_empty() {
+                1
+            } else {
+                predecessors.iter().map(|&p| counts[p]).sum()
+            };
+        }
+        counts
+    }
+
+    fn counts_to_ends(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.nodes.len()];
+        for &end in &self.ends {
+            counts[end] += 1;
+        }
+        for &node in self.order.iter().rev() {
+            for &predecessor in &self.predecessors[node] {
+                counts[predecessor] += counts[node];
+            }
+        }
+        counts
+    }
+}
+
+fn topological_order(predecessors: &[Vec<usize>]) -> Vec<usize> {
+    let mut successors = vec![Vec::new(); predecessors.len()];
+    for (node, froms) in predecessors.iter().enumerate() {
+        for &from in froms {
+            successors[from].push(node);
+        }
+    }
+
+    let mut remaining: Vec<_> = predecessors.iter().map(Vec::len).collect();
+    let mut order: Vec<_> = (0..predecessors.len())
+        .filter(|&node| remaining[node] == 0)
+        .collect();
+    let mut i = 0;
+    while let Some(&node) = order.get(i) {
+        for &successor in &successors[node] {
+            remaining[successor] -= 1;
+            if remaining[successor] == 0 {
+                order.push(successor);
+            }
+        }
+        i += 1;
+    }
+    order
 }
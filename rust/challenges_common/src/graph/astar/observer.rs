@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+/// What a search went through, see [`super::astar_with_observer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Nodes whose nexts were asked for.
+    pub expanded: usize,
+    /// Steps returned by `next`, kept or not.
+    pub generated: usize,
+    /// Already expanded nodes found again for a lower cost.
+    pub reopened: usize,
+    /// Largest number of entries in the queue, outdated ones included.
+    pub peak_queue: usize,
+    /// Time since the search started, kept up to date for the observer.
+    pub elapsed: Duration,
+}
+
+/// Hooks called while searching, to log progress or dump the frontier.
+pub trait SearchObserver<N, C> {
+    /// `node` is about to be expanded, reached for `cost`.
+    fn on_expand(&mut self, _node: &N, _cost: C, _stats: &SearchStats) {}
+
+    /// `node` is queued, reached for `cost` with an estimated total of `score`.
+    fn on_push(&mut self, _node: &N, _cost: C, _score: C) {}
+}
+
+impl<N, C> SearchObserver<N, C> for () {}
+
+impl<N, C, O: SearchObserver<N, C>> SearchObserver<N, C> for &mut O {
+    fn on_expand(&mut self, node: &N, cost: C, stats: &SearchStats) {
+        (**self).on_expand(node, cost, stats)
+    }
+
+    fn on_push(&mut self, node: &N, cost: C, score: C) {
+        (**self).on_push(node, cost, score)
+    }
+}
+
+/// Observer made of two closures.
+pub struct Callbacks<E, P> {
+    pub on_expand: E,
+    pub on_push: P,
+}
+
+impl<N, C, E, P> SearchObserver<N, C> for Callbacks<E, P>
+where
+    E: FnMut(&N, C, &SearchStats),
+    P: FnMut(&N, C, C),
+{
+    fn on_expand(&mut self, node: &N, cost: C, stats: &SearchStats) {
+        (self.on_expand)(node, cost, stats)
+    }
+
+    fn on_push(&mut self, node: &N, cost: C, score: C) {
+        (self.on_push)(node, cost, score)
+    }
+}
@@ -0,0 +1,460 @@
+use crate::graph::{Cost, Node, Path, SearchLimits, SearchOutcome, Step};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Shortest path (in number of moves) from `start` to `goal`, searching
+/// breadth-first from both ends until they meet. `prev` gives the nodes from
+/// which a node can be reached.
+pub fn bidirectional_search<N, Nexts, Prevs>(
+    start: N,
+    goal: N,
+    next: impl FnMut(&N) -> Nexts,
+    prev: impl FnMut(&N) -> Prevs,
+) -> Option<Path<N, usize>>
+where
+    N: Node + Clone,
+    Nexts: IntoIterator<Item = N>,
+    Prevs: IntoIterator<Item = N>,
+{
+    bidirectional_search_with_limits(start, goal, next, prev, SearchLimits::default()).found()
+}
+
+/// [`bidirectional_search`] giving up once one of the `limits` is reached,
+/// checked before each layer. The best so far is then the path from `start`
+/// to the last node reached from it.
+pub fn bidirectional_search_with_limits<N, Nexts, Prevs>(
+    start: N,
+    goal: N,
+    mut next: impl FnMut(&N) -> Nexts,
+    mut prev: impl FnMut(&N) -> Prevs,
+    limits: SearchLimits<usize>,
+) -> SearchOutcome<Path<N, usize>>
+where
+    N: Node + Clone,
+    Nexts: IntoIterator<Item = N>,
+    Prevs: IntoIterator<Item = N>,
+{
+    let mut forward = Layers::new(start);
+    let mut backward = Layers::new(goal);
+
+    loop {
+        // paths not found yet go one layer further on either side
+        let bound = forward.depth + backward.depth + 1;
+        if limits.reached(bound, forward.expanded + backward.expanded) {
+            return SearchOutcome::LimitReached {
+                best_so_far: forward.path_to_last(),
+            };
+        }
+
+        let meeting = if forward.frontier.len() <= backward.frontier.len() {
+            forward.expand(&backward, &mut next)
+        } else {
+            backward.expand(&forward, &mut prev)
+        };
+
+        if let Some((cost, meeting)) = meeting {
+            return SearchOutcome::Found(join(&forward.reached, &backward.reached, meeting, cost));
+        }
+        if forward.frontier.is_empty() || backward.frontier.is_empty() {
+            return SearchOutcome::Exhausted;
+        }
+    }
+}
+
+/// Cheapest path from `start` to `goal`, running Dijkstra from both ends until
+/// they meet. `prev` gives the steps from which a node can be reached, with
+/// the cost of these steps.
+pub fn bidirectional_search_weighted<N, C, Nexts, Prevs>(
+    start: N,
+    goal: N,
+    next: impl FnMut(&N) -> Nexts,
+    prev: impl FnMut(&N) -> Prevs,
+) -> Option<Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+    Prevs: IntoIterator<Item = Step<N, C>>,
+{
+    bidirectional_search_weighted_with_limits(start, goal, next, prev, SearchLimits::default())
+        .found()
+}
+
+/// [`bidirectional_search_weighted`] giving up once one of the `limits` is
+/// reached. The best so far is then the cheapest path found between `start`
+/// and `goal`, if any, or the path from `start` to the last node settled from
+/// it.
+pub fn bidirectional_search_weighted_with_limits<N, C, Nexts, Prevs>(
+    start: N,
+    goal: N,
+    mut next: impl FnMut(&N) -> Nexts,
+    mut prev: impl FnMut(&N) -> Prevs,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<Path<N, C>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+    Prevs: IntoIterator<Item = Step<N, C>>,
+{
+    let mut forward = Frontier::new(start);
+    let mut backward = Frontier::new(goal.clone());
+    let mut best: Option<(C, N)> = forward
+        .reached
+        .contains_key(&goal)
+        .then(|| (C::default(), goal));
+
+    // once a side has settled all it can reach, any path between start and
+    // goal went through the other side's nodes and has been met already
+    while let (Some(forward_cost), Some(backward_cost)) = (forward.min_cost(), backward.min_cost())
+    {
+        let bound = forward_cost + backward_cost;
+        if best
+            .as_ref()
+            .is_some_and(|(best_cost, _)| bound >= *best_cost)
+        {
+            break;
+        }
+        if limits.reached(bound, forward.expanded + backward.expanded) {
+            let best_so_far = match best {
+                Some((cost, meeting)) => join(&forward.reached, &backward.reached, meeting, cost),
+                None => forward.path_to_last(),
+            };
+            return SearchOutcome::LimitReached { best_so_far };
+        }
+
+        let meeting = if forward_cost <= backward_cost {
+            forward.expand(&backward, &mut next)
+        } else {
+            backward.expand(&forward, &mut prev)
+        };
+        if let Some((cost, node)) = meeting {
+            if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                best = Some((cost, node));
+            }
+        }
+    }
+
+    match best {
+        Some((cost, meeting)) => {
+            SearchOutcome::Found(join(&forward.reached, &backward.reached, meeting, cost))
+        }
+        None => SearchOutcome::Exhausted,
+    }
+}
+
+type Reached<N, C> = HashMap<N, (C, Option<N>)>;
+
+struct Layers<N> {
+    reached: Reached<N, usize>,
+    frontier: Vec<N>,
+    depth: usize,
+    expanded: usize,
+    last: N,
+}
+
+impl<N: Node + Clone> Layers<N> {
+    fn new(start: N) -> Self {
+        Self {
+            reached: HashMap::from([(start.clone(), (0, None))]),
+            frontier: vec![start.clone()],
+            depth: 0,
+            expanded: 0,
+            last: start,
+        }
+    }
+
+    fn path_to_last(&self) -> Path<N, usize> {
+        path_to(&self.reached, self.last.clone())
+    }
+
+    /// Expands a whole layer, returns the best meeting with `other`, if any.
+    fn expand<Nexts>(
+        &mut self,
+        other: &Self,
+        next: &mut impl FnMut(&N) -> Nexts,
+    ) -> Option<(usize, N)>
+    where
+        Nexts: IntoIterator<Item = N>,
+    {
+        if let Some((depth, _)) = self.frontier.iter().find_map(|n| other.reached.get(n)) {
+            // only possible when start is goal
+            return Some((*depth, self.frontier[0].clone()));
+        }
+
+        self.depth += 1;
+        let mut meeting: Option<(usize, N)> = None;
+        for from in std::mem::take(&mut self.frontier) {
+            self.expanded += 1;
+            for to in next(&from) {
+                if self.reached.contains_key(&to) {
+                    continue;
+                }
+                self.reached
+                    .insert(to.clone(), (self.depth, Some(from.clone())));
+                self.last = to.clone();
+                if let Some((other_depth, _)) = other.reached.get(&to) {
+                    let cost = self.depth + other_depth;
+                    if meeting.as_ref().is_none_or(|(best, _)| cost < *best) {
+                        meeting = Some((cost, to.clone()));
+                    }
+                }
+                self.frontier.push(to);
+            }
+        }
+
+        meeting
+    }
+}
+
+struct Frontier<N, C> {
+    reached: Reached<N, C>,
+    // the heap only holds indexes in `nodes` so that N does not need to be Ord
+    nodes: Vec<N>,
+    queue: BinaryHeap<Reverse<(C, usize)>>,
+    expanded: usize,
+    /// Index in `nodes` of the last settled node.
+    last: usize,
+}
+
+impl<N: Node + Clone, C: Cost> Frontier<N, C> {
+    fn new(start: N) -> Self {
+        Self {
+            reached: HashMap::from([(start.clone(), (C::default(), None))]),
+            nodes: vec![start],
+            queue: BinaryHeap::from([Reverse((C::default(), 0))]),
+            expanded: 0,
+            last: 0,
+        }
+    }
+
+    fn path_to_last(&self) -> Path<N, C> {
+        path_to(&self.reached, self.nodes[self.last].clone())
+    }
+
+    /// Cost of the next node to settle, dropping outdated queue entries.
+    fn min_cost(&mut self) -> Option<C> {
+        while let Some(Reverse((cost, index))) = self.queue.peek() {
+            if self.reached[&self.nodes[*index]].0 == *cost {
+                return Some(*cost);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    /// Settles the next node, returns the best meeting with `other` found.
+    fn expand<Nexts>(&mut self, other: &Self, next: &mut impl FnMut(&N) -> Nexts) -> Option<(C, N)>
+    where
+        Nexts: IntoIterator<Item = Step<N, C>>,
+    {
+        let Reverse((cost, index)) = self.queue.pop()?;
+        let from = self.nodes[index].clone();
+        self.expanded += 1;
+        self.last = index;
+
+        let mut meeting: Option<(C, N)> = None;
+        for Step {
+            to,
+            additional_cost,
+        } in next(&from)
+        {
+            let cost = cost + additional_cost;
+            if self
+                .reached
+                .get(&to)
+                .is_some_and(|(existing, _)| *existing <= cost)
+            {
+                continue;
+            }
+
+            if let Some((other_cost, _)) = other.reached.get(&to) {
+                let total = cost + *other_cost;
+                if meeting.as_ref().is_none_or(|(best, _)| total < *best) {
+                    meeting = Some((total, to.clone()));
+                }
+            }
+            self.reached.insert(to.clone(), (cost, Some(from.clone())));
+            self.queue.push(Reverse((cost, self.nodes.len())));
+            self.nodes.push(to);
+        }
+
+        meeting
+    }
+}
+
+fn join<N: Node + Clone, C>(
+    forward: &Reached<N, C>,
+    backward: &Reached<N, C>,
+    meeting: N,
+    cost: C,
+) -> Path<N, C> {
+    let mut nodes = vec![meeting.clone()];
+    while let Some((_, Some(previous))) = forward.get(nodes.last().unwrap()) {
+        nodes.push(previous.clone());
+    }
+    nodes.reverse();
+
+    let mut current = meeting;
+    while let Some((_, Some(next))) = backward.get(&current) {
+        nodes.push(next.clone());
+        current = next.clone();
+    }
+
+    Path { nodes, cost }
+}
+
+/// Path from the start of the side that reached `to`.
+fn path_to<N: Node + Clone, C: Copy>(reached: &Reached<N, C>, to: N) -> Path<N, C> {
+    let cost = reached[&to].0;
+    join(reached, &HashMap::new(), to, cost)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::astar;
+
+    fn edges() -> Vec<(u8, u8, u32)> {
+        vec![
+            (0, 1, 7),
+            (0, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (1, 4, 1),
+            (4, 5, 10),
+            (3, 5, 20),
+            (6, 0, 1),
+        ]
+    }
+
+    fn next(edges: &[(u8, u8, u32)], from: u8) -> Vec<Step<u8, u32>> {
+        edges
+            .iter()
+            .filter(|(at, _, _)| *at == from)
+            .map(|&(_, to, additional_cost)| Step {
+                to,
+                additional_cost,
+            })
+            .collect()
+    }
+
+    fn prev(edges: &[(u8, u8, u32)], to: u8) -> Vec<Step<u8, u32>> {
+        edges
+            .iter()
+            .filter(|(_, at, _)| *at == to)
+            .map(|&(from, _, additional_cost)| Step {
+                to: from,
+                additional_cost,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn uniform_search_finds_the_fewest_moves() {
+        let edges = edges();
+        let search = |start, goal| {
+            bidirectional_search(
+                start,
+                goal,
+                |&n| next(&edges, n).into_iter().map(|step| step.to),
+                |&n| prev(&edges, n).into_iter().map(|step| step.to),
+            )
+        };
+
+        let path = search(6, 5).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes.len(), 5);
+        assert_eq!((path.nodes[0], path.nodes[4]), (6, 5));
+        assert!(path.nodes.windows(2).all(|pair| edges
+            .iter()
+            .any(|&(from, to, _)| (from, to) == (pair[0], pair[1]))));
+
+        let path = search(3, 3).unwrap();
+        assert_eq!((path.nodes, path.cost), (vec![3], 0));
+        assert!(search(5, 0).is_none());
+    }
+
+    #[test]
+    fn stops_on_limits_with_a_partial_path() {
+        let edges = edges();
+        let limits = SearchLimits {
+            max_expansions: Some(2),
+            ..Default::default()
+        };
+
+        let SearchOutcome::LimitReached { best_so_far } = bidirectional_search_weighted_with_limits(
+            6,
+            5,
+            |&n| next(&edges, n),
+            |&n| prev(&edges, n),
+            limits,
+        ) else {
+            panic!("expected the expansions limit to be reached")
+        };
+        assert_eq!((best_so_far.nodes, best_so_far.cost), (vec![6], 0));
+
+        let SearchOutcome::LimitReached { best_so_far } = bidirectional_search_with_limits(
+            6,
+            5,
+            |&n| next(&edges, n).into_iter().map(|step| step.to),
+            |&n| prev(&edges, n).into_iter().map(|step| step.to),
+            SearchLimits {
+                max_cost: Some(2),
+                ..Default::default()
+            },
+        ) else {
+            panic!("expected the cost limit to be reached")
+        };
+        assert_eq!(best_so_far.nodes[0], 6);
+        assert!(matches!(
+            bidirectional_search_with_limits(
+                5,
+                0,
+                |&n| next(&edges, n).into_iter().map(|step| step.to),
+                |&n| prev(&edges, n).into_iter().map(|step| step.to),
+                SearchLimits::default(),
+            ),
+            SearchOutcome::Exhausted
+        ));
+    }
+
+    #[test]
+    fn weighted_search_matches_astar() {
+        let edges = edges();
+        for (start, goal) in [(0, 5), (6, 4), (2, 2), (6, 5)] {
+            let expected = astar(start, |&n| next(&edges, n), |&n| n == goal, |_| 0).unwrap();
+            let path = bidirectional_search_weighted(
+                start,
+                goal,
+                |&n| next(&edges, n),
+                |&n| prev(&edges, n),
+            )
+            .unwrap();
+
+            assert_eq!(path.cost, expected.cost);
+            assert_eq!(path.nodes, expected.nodes);
+        }
+        assert!(
+            bidirectional_search_weighted(5, 0, |&n| next(&edges, n), |&n| prev(&edges, n))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn weighted_search_stops_when_a_side_runs_out() {
+        let edges = edges();
+        let mut prevs = 0;
+        let path = bidirectional_search_weighted(
+            5,
+            0,
+            |&n| next(&edges, n),
+            |&n| {
+                prevs += 1;
+                prev(&edges, n)
+            },
+        );
+        assert!(path.is_none());
+        assert_eq!(prevs, 0);
+    }
+}
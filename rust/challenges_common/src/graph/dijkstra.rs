@@ -0,0 +1,136 @@
+use super::astar::{Search, Stop, Visit};
+use crate::graph::{Cost, Node, SearchLimits, SearchOutcome, Step};
+use std::collections::HashMap;
+
+/// Best cost from `start` to every reachable node, with the node it is reached
+/// from (`None` for `start`).
+pub fn dijkstra_all<N, C, Nexts>(
+    start: N,
+    next: impl FnMut(&N) -> Nexts,
+) -> HashMap<N, (C, Option<N>)>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    settled(dijkstra_with_limits(start, next, SearchLimits::default()))
+}
+
+/// Like [`dijkstra_all`], only keeping nodes reachable for at most `max_cost`.
+pub fn dijkstra_within<N, C, Nexts>(
+    start: N,
+    next: impl FnMut(&N) -> Nexts,
+    max_cost: C,
+) -> HashMap<N, (C, Option<N>)>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    let limits = SearchLimits {
+        max_cost: Some(max_cost),
+        ..Default::default()
+    };
+    settled(dijkstra_with_limits(start, next, limits))
+}
+
+/// [`dijkstra_all`] giving up once one of the `limits` is reached, with the
+/// nodes whose best cost is known so far.
+///
+/// The costs are `Found` once every reachable node is settled: it is never
+/// `Exhausted`.
+pub fn dijkstra_with_limits<N, C, Nexts>(
+    start: N,
+    mut next: impl FnMut(&N) -> Nexts,
+    limits: SearchLimits<C>,
+) -> SearchOutcome<HashMap<N, (C, Option<N>)>>
+where
+    N: Node + Clone,
+    C: Cost,
+    Nexts: IntoIterator<Item = Step<N, C>>,
+{
+    let no_heuristic = |_: &N| C::default();
+    let mut search = Search::new([(start, C::default())], &no_heuristic);
+    let stop = search.run(&mut next, &no_heuristic, |_, _| Visit::Expand, &limits);
+
+    match stop {
+        Stop::Limit(_) => SearchOutcome::LimitReached {
+            best_so_far: search.into_settled(),
+        },
+        _ => SearchOutcome::Found(search.into_settled()),
+    }
+}
+
+fn settled<T: Default>(outcome: SearchOutcome<T>) -> T {
+    match outcome {
+        SearchOutcome::Found(found) | SearchOutcome::LimitReached { best_so_far: found } => found,
+        SearchOutcome::Exhausted => T::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn next(edges: &[(u8, u8, u32)]) -> impl FnMut(&u8) -> Vec<Step<u8, u32>> + '_ {
+        |&from| {
+            edges
+                .iter()
+                .filter(|(at, _, _)| *at == from)
+                .map(|&(_, to, additional_cost)| Step {
+                    to,
+                    additional_cost,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn every_reachable_node() {
+        let edges = [(0, 1, 10), (1, 3, 20), (0, 2, 20), (2, 3, 5), (4, 0, 1)];
+        let distances = dijkstra_all(0, next(&edges));
+
+        assert_eq!(
+            distances,
+            HashMap::from([
+                (0, (0, None)),
+                (1, (10, Some(0))),
+                (2, (20, Some(0))),
+                (3, (25, Some(2))),
+            ])
+        );
+    }
+
+    #[test]
+    fn within_a_budget() {
+        let edges = [(0, 1, 10), (1, 3, 20), (0, 2, 20), (2, 3, 5)];
+        let distances = dijkstra_within(0, next(&edges), 20);
+
+        let mut reached: Vec<_> = distances.keys().copied().collect();
+        reached.sort();
+        assert_eq!(reached, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn stops_after_max_expansions() {
+        let edges = [(0, 1, 10), (1, 3, 20), (0, 2, 20), (2, 3, 5)];
+        let limits = SearchLimits {
+            max_expansions: Some(2),
+            ..Default::default()
+        };
+
+        let SearchOutcome::LimitReached { best_so_far } =
+            dijkstra_with_limits(0, next(&edges), limits)
+        else {
+            panic!("expected the expansions limit to be reached")
+        };
+        assert_eq!(
+            best_so_far,
+            HashMap::from([(0, (0, None)), (1, (10, Some(0)))])
+        );
+        assert!(matches!(
+            dijkstra_with_limits(0, next(&edges), SearchLimits::default()),
+            SearchOutcome::Found(distances) if distances.len() == 4
+        ));
+    }
+}
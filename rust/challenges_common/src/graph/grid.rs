@@ -1,14 +1,39 @@
 use itertools::Itertools;
 use num_traits::{zero, Num, PrimInt, Signed};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::fmt::{Display, Formatter};
+use std::hash::Hash;
 use std::ops::{Add, AddAssign, Mul};
 use std::str::FromStr;
 
+mod components;
+mod distances;
+mod junctions;
+mod lines;
+mod neighbourhood;
+mod raster;
+mod render;
+mod shortest_path;
+mod sparse;
+mod topology;
+mod transform;
+pub use components::{Component, ComponentId, Components};
+pub use distances::DistanceMap;
+pub use junctions::{Corridor, JunctionGraph};
+pub use lines::{Line, LineMut};
+pub use neighbourhood::{Direction8, Neighbourhood};
+pub use raster::{bresenham, polyline, segment};
+pub use render::{Colour, GridRenderer, Mark};
+pub use sparse::SparseGrid;
+pub use topology::Topology;
+pub use transform::{GridView, Symmetry};
+
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub struct Grid<N, U = usize> {
     width: U,
     content: Vec<N>,
+    topology: Topology<U>,
 }
 
 impl<N, U> Grid<N, U>
@@ -19,7 +44,11 @@ where
         self.width
     }
 
+    /// 0 for an empty grid, whose width may be 0 too.
     pub fn height(&self) -> U {
+        if self.width.is_zero() {
+            return U::zero();
+        }
         U::from(self.content.len()).unwrap() / self.width
     }
 
@@ -39,6 +68,7 @@ where
         Grid {
             width: self.width,
             content: self.content.iter().map(f).collect(),
+            topology: self.topology,
         }
     }
 
@@ -46,6 +76,7 @@ where
         Grid {
             width: self.width,
             content: self.entries().map(f).collect(),
+            topology: self.topology,
         }
     }
 
@@ -70,7 +101,7 @@ where
 
     pub fn is_coord_inside(&self, coord: &Coord<U>) -> bool {
         U::zero() <= coord.y
-            && coord.y < self.width
+            && coord.y < self.height()
             && U::zero() <= coord.x
             && coord.x < self.width()
     }
@@ -102,12 +133,6 @@ where
             .find(|(_i, n)| predicate(n))
             .and_then(|(i, _n)| self.get_coord_from_index(i))
     }
-
-    pub fn neighbours(&self, coord: &Coord<U>) -> impl Iterator<Item = (Coord<U>, &N)> + '_ {
-        coord
-            .neighbours(false)
-            .filter_map(|coord| self.get(&coord).map(move |n| (coord, n)))
-    }
 }
 
 impl<IntoIt, N, U> From<IntoIt> for Grid<N, U>
@@ -134,24 +159,32 @@ where
         Self {
             width: width.unwrap_or(zero()),
             content,
+            topology: Topology::Bounded,
         }
     }
 }
 
 use thiserror::Error;
 
+/// Lines and columns are 1-based.
 #[derive(Error, Debug)]
 pub enum CannotParseGrid<T: std::error::Error + Sized = CannotParseElementFromChar> {
-    #[error("Cannot parse grid from \"{0}\": {1}")]
-    CannotParseNode(String, #[source] T),
+    #[error("Cannot parse grid: '{char}' at line {line}, column {column}: {source}")]
+    CannotParseNode {
+        line: usize,
+        column: usize,
+        char: char,
+        #[source]
+        source: T,
+    },
     #[error(
-        "Cannot parse grid from \"{str}\": all lines does not have the same length:\
-     line {line_index} has length {line_length}"
+        "Cannot parse grid: all lines does not have the same length: \
+     line {line} has length {line_length} instead of {expected_length}"
     )]
     AllLinesDoesNotHaveSameLength {
-        str: String,
-        line_index: usize,
+        line: usize,
         line_length: usize,
+        expected_length: usize,
     },
 }
 
@@ -182,35 +215,88 @@ where
     type Err = CannotParseGrid<N::Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |_, c| N::try_from(c))
+    }
+}
+
+/// Coords of every marker found by [`Grid::parse_with_markers`].
+pub type Markers<U = usize> = HashMap<char, Vec<Coord<U>>>;
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    /// Parses the grid and takes out every `markers` char: its coords are
+    /// collected and the cell gets the associated value instead.
+    ///
+    /// Every marker has an entry in the returned map, even if it was not found.
+    pub fn parse_with_markers(
+        s: &str,
+        markers: &[(char, N)],
+    ) -> Result<(Self, Markers<U>), CannotParseGrid<N::Error>>
+    where
+        N: TryFrom<char> + Clone,
+        N::Error: Sized + std::error::Error,
+        U: Hash,
+    {
+        let mut found: HashMap<_, _> = markers.iter().map(|(c, _)| (*c, Vec::new())).collect();
+
+        let grid = Self::parse_with(s, |coord, c| {
+            match markers.iter().find(|(marker, _)| *marker == c) {
+                Some((_, node)) => {
+                    found.get_mut(&c).unwrap().push(coord);
+                    Ok(node.clone())
+                }
+                None => N::try_from(c),
+            }
+        })?;
+
+        Ok((grid, found))
+    }
+
+    fn parse_with<E: std::error::Error>(
+        s: &str,
+        mut parse_node: impl FnMut(Coord<U>, char) -> Result<N, E>,
+    ) -> Result<Self, CannotParseGrid<E>> {
         let mut content = Vec::with_capacity(s.len());
         let mut width = None;
 
-        for (line_index, line) in s.lines().enumerate() {
+        for (y, line) in s.lines().enumerate() {
+            let line_length = line.chars().count();
             match width {
                 None => {
-                    width = Some(line.len());
+                    width = Some(line_length);
                 }
-                Some(width) if width != line.len() => {
+                Some(width) if width != line_length => {
                     return Err(CannotParseGrid::AllLinesDoesNotHaveSameLength {
-                        str: s.to_string(),
-                        line_index,
-                        line_length: line.len(),
+                        line: y + 1,
+                        line_length,
+                        expected_length: width,
                     })
                 }
                 _ => (),
             };
 
-            for c in line.chars() {
-                content.push(
-                    N::try_from(c)
-                        .map_err(|e| CannotParseGrid::CannotParseNode(s.to_string(), e))?,
-                );
+            for (x, c) in line.chars().enumerate() {
+                let coord = Coord {
+                    x: U::from(x).unwrap(),
+                    y: U::from(y).unwrap(),
+                };
+                content.push(parse_node(coord, c).map_err(|source| {
+                    CannotParseGrid::CannotParseNode {
+                        line: y + 1,
+                        column: x + 1,
+                        char: c,
+                        source,
+                    }
+                })?);
             }
         }
 
         Ok(Self {
             width: width.map(|width| U::from(width).unwrap()).unwrap_or(zero()),
             content,
+            topology: Topology::Bounded,
         })
     }
 }
@@ -284,30 +370,6 @@ impl<U: PrimInt> Coord<U> {
         let dist = |a: U, b: U| if a > b { a - b } else { b - a };
         dist(self.x, to.x) + dist(self.y, to.y)
     }
-
-    pub fn neighbours(&self, with_diag: bool) -> impl Iterator<Item = Self> {
-        let mut result = Vec::with_capacity(if with_diag { 8 } else { 4 });
-
-        use Direction::*;
-        [Up, Down, Left, Right]
-            .into_iter()
-            .flat_map(|dir| self.try_at(dir))
-            .for_each(|coord| result.push(coord));
-
-        if with_diag {
-            [Up, Down]
-                .into_iter()
-                .flat_map(|dir| self.try_at(dir))
-                .flat_map(|coord| {
-                    [Left, Right]
-                        .into_iter()
-                        .filter_map(move |dir| coord.try_at(dir))
-                })
-                .for_each(|coord| result.push(coord));
-        }
-
-        result.into_iter()
-    }
 }
 
 impl<U: PrimInt + Signed> Coord<U> {
@@ -513,19 +575,19 @@ mod test {
     #[test]
     fn neighbours_of_11_with_diag_are_8() {
         let coord = Coord { x: 1, y: 1 };
-        assert_eq!(coord.neighbours(true).count(), 8)
+        assert_eq!(coord.neighbours(&Neighbourhood::Moore).count(), 8)
     }
 
     #[test]
     fn neighbours_of_11_are_4() {
         let coord = Coord { x: 1, y: 1 };
-        assert_eq!(coord.neighbours(false).count(), 4)
+        assert_eq!(coord.neighbours(&Neighbourhood::VonNeumann).count(), 4)
     }
 
     #[test]
     fn neighbours_of_00_are_2() {
         let coord = Coord::<u32> { x: 0, y: 0 };
-        assert_eq!(coord.neighbours(false).count(), 2)
+        assert_eq!(coord.neighbours(&Neighbourhood::VonNeumann).count(), 2)
     }
 
     #[test]
@@ -533,6 +595,7 @@ mod test {
         let grid = Grid {
             width: 2,
             content: vec![1u8, 2, 3, 4],
+            topology: Topology::Bounded,
         };
         assert_eq!(grid.get(&Coord { x: 2, y: 0 }), None);
         assert_eq!(grid.get(&Coord { x: -1, y: 0 }), None);
@@ -545,6 +608,7 @@ mod test {
         let grid = Grid {
             width: 2,
             content: vec![1u8, 2, 3, 4],
+            topology: Topology::Bounded,
         };
 
         assert_eq!(grid.get(&Coord { x: 0, y: 0 }), Some(&1));
@@ -552,4 +616,77 @@ mod test {
         assert_eq!(grid.get(&Coord { x: 0, y: 1 }), Some(&3));
         assert_eq!(grid.get(&Coord { x: 1, y: 1 }), Some(&4));
     }
+
+    #[test]
+    fn coord_inside_uses_height_for_rows() {
+        let grid = Grid {
+            width: 3,
+            content: vec![1u8, 2, 3, 4, 5, 6],
+            topology: Topology::Bounded,
+        };
+
+        assert!(grid.is_coord_inside(&Coord { x: 2, y: 1 }));
+        assert!(!grid.is_coord_inside(&Coord { x: 0, y: 2 }));
+        assert!(!grid.is_coord_inside(&Coord { x: 3, y: 0 }));
+    }
+
+    #[test]
+    fn parse_error_locates_the_char() {
+        let error = "..\n.x\n".parse::<Grid<Pixel>>().unwrap_err();
+        assert!(matches!(
+            error,
+            CannotParseGrid::CannotParseNode {
+                line: 2,
+                column: 2,
+                char: 'x',
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_error_on_ragged_lines() {
+        let error = "..\n...\n".parse::<Grid<Pixel>>().unwrap_err();
+        assert!(matches!(
+            error,
+            CannotParseGrid::AllLinesDoesNotHaveSameLength {
+                line: 2,
+                line_length: 3,
+                expected_length: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_with_markers() {
+        let (grid, markers) = Grid::<Pixel>::parse_with_markers(
+            "S.#\n..E\n",
+            &[('S', Pixel::Empty), ('E', Pixel::Empty), ('@', Pixel::Wall)],
+        )
+        .unwrap();
+
+        assert_eq!(grid.get(&Coord { x: 0, y: 0 }), Some(&Pixel::Empty));
+        assert_eq!(grid.get(&Coord { x: 2, y: 0 }), Some(&Pixel::Wall));
+        assert_eq!(markers[&'S'], vec![Coord { x: 0, y: 0 }]);
+        assert_eq!(markers[&'E'], vec![Coord { x: 2, y: 1 }]);
+        assert_eq!(markers[&'@'], vec![]);
+    }
+
+    #[derive(Debug, PartialEq, Clone)]
+    enum Pixel {
+        Empty,
+        Wall,
+    }
+
+    impl TryFrom<char> for Pixel {
+        type Error = CannotParseElementFromChar;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '.' => Ok(Pixel::Empty),
+                '#' => Ok(Pixel::Wall),
+                _ => Err(c.into()),
+            }
+        }
+    }
 }
@@ -0,0 +1,169 @@
+use crate::geom::Rect;
+use crate::graph::{Coord, Grid, Neighbourhood};
+use num_traits::PrimInt;
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+pub type ComponentId = usize;
+
+/// Result of [`Grid::label_components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components<U = usize> {
+    pub labels: Grid<ComponentId, U>,
+    pub components: Vec<Component<U>>,
+}
+
+impl<U: PrimInt> Components<U> {
+    pub fn component_of(&self, coord: &Coord<U>) -> Option<&Component<U>> {
+        self.labels.get(coord).map(|&id| &self.components[id])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component<U = usize> {
+    pub id: ComponentId,
+    /// First cell of the component in row-major order.
+    pub start: Coord<U>,
+    pub size: usize,
+    pub bounds: Rect<U>,
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt + Hash,
+{
+    /// Every cell reachable from `start` through 4-neighbours, moving from a
+    /// cell to a neighbour only when `same_region(from, to)` holds.
+    pub fn flood_fill(
+        &self,
+        start: &Coord<U>,
+        same_region: impl Fn(&N, &N) -> bool,
+    ) -> HashSet<Coord<U>> {
+        let mut region = HashSet::new();
+        if self.get(start).is_none() {
+            return region;
+        }
+
+        let mut queue = VecDeque::from([*start]);
+        region.insert(*start);
+        while let Some(coord) = queue.pop_front() {
+            let node = self.get(&coord).unwrap();
+            for (neighbour, neighbour_node) in self.neighbours(&coord, &Neighbourhood::VonNeumann) {
+                if same_region(node, neighbour_node) && region.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        region
+    }
+
+    /// Splits the grid into connected components: two cells adjacent according
+    /// to `neighbourhood` belong together when `same_component` holds.
+    pub fn label_components(
+        &self,
+        neighbourhood: &Neighbourhood,
+        same_component: impl Fn(&N, &N) -> bool,
+    ) -> Components<U> {
+        let mut labels: Grid<Option<ComponentId>, U> = self.map(|_| None);
+        let mut components = Vec::new();
+
+        for start in self.coords() {
+            if labels.get(&start).unwrap().is_some() {
+                continue;
+            }
+
+            let id = components.len();
+            let mut component = Component {
+                id,
+                start,
+                size: 0,
+                bounds: Rect::point(start),
+            };
+
+            *labels.get_mut(&start).unwrap() = Some(id);
+            let mut queue = VecDeque::from([start]);
+            while let Some(coord) = queue.pop_front() {
+                component.size += 1;
+                component.bounds = component.bounds.extended_to(&coord);
+
+                let node = self.get(&coord).unwrap();
+                for (neighbour, neighbour_node) in self.neighbours(&coord, neighbourhood) {
+                    let label = labels.get_mut(&neighbour).unwrap();
+                    if label.is_none() && same_component(node, neighbour_node) {
+                        *label = Some(id);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        Components {
+            labels: labels.map(|label| label.unwrap()),
+            components,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn garden() -> Grid<char> {
+        "AAAA\nBBCD\nBBCC\nEEEC\n".parse().unwrap()
+    }
+
+    #[test]
+    fn flood_fill_same_plant() {
+        let garden = garden();
+        let region = garden.flood_fill(&Coord { x: 2, y: 1 }, |a, b| a == b);
+
+        assert_eq!(
+            region,
+            HashSet::from([
+                Coord { x: 2, y: 1 },
+                Coord { x: 2, y: 2 },
+                Coord { x: 3, y: 2 },
+                Coord { x: 3, y: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn flood_fill_outside_is_empty() {
+        assert!(garden()
+            .flood_fill(&Coord { x: 4, y: 0 }, |a, b| a == b)
+            .is_empty());
+    }
+
+    #[test]
+    fn label_garden_regions() {
+        let garden = garden();
+        let components = garden.label_components(&Neighbourhood::VonNeumann, |a, b| a == b);
+
+        let sizes: Vec<_> = components.components.iter().map(|c| c.size).collect();
+        assert_eq!(sizes, vec![4, 4, 4, 1, 3]);
+
+        let c = components.component_of(&Coord { x: 3, y: 3 }).unwrap();
+        assert_eq!(c.start, Coord { x: 2, y: 1 });
+        assert_eq!(
+            c.bounds,
+            Rect::new(Coord { x: 2, y: 1 }, Coord { x: 3, y: 3 })
+        );
+        assert_eq!(components.labels.get(&Coord { x: 3, y: 1 }), Some(&3));
+    }
+
+    #[test]
+    fn diagonals_join_components() {
+        let grid: Grid<char> = "#.#\n.#.\n#.#\n".parse().unwrap();
+
+        let four = grid.label_components(&Neighbourhood::VonNeumann, |a, b| a == b);
+        assert_eq!(four.components.len(), 9);
+
+        let eight = grid.label_components(&Neighbourhood::Moore, |a, b| a == b);
+        assert_eq!(eight.components.len(), 2);
+        assert_eq!(eight.components[0].size, 5);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::graph::{Coord, Cost, Grid, Neighbourhood, Path};
+use num_traits::PrimInt;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Result of [`Grid::distance_map`] and [`Grid::weighted_distance_map`]: for
+/// every reached cell, its distance to the nearest source, the cell it was
+/// reached from and the index (in the given sources) of that nearest source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMap<C, U = usize> {
+    pub distances: Grid<Option<C>, U>,
+    pub predecessors: Grid<Option<Coord<U>>, U>,
+    pub nearest_sources: Grid<Option<usize>, U>,
+}
+
+impl<C, U> DistanceMap<C, U>
+where
+    C: Cost,
+    U: PrimInt,
+{
+    fn new<N>(grid: &Grid<N, U>) -> Self {
+        Self {
+            distances: grid.map(|_| None),
+            predecessors: grid.map(|_| None),
+            nearest_sources: grid.map(|_| None),
+        }
+    }
+
+    pub fn distance(&self, coord: &Coord<U>) -> Option<C> {
+        *self.distances.get(coord)?
+    }
+
+    pub fn nearest_source(&self, coord: &Coord<U>) -> Option<usize> {
+        *self.nearest_sources.get(coord)?
+    }
+
+    /// Shortest path from the nearest source to `coord`.
+    pub fn path_to(&self, coord: &Coord<U>) -> Option<Path<Coord<U>, C>> {
+        let cost = self.distance(coord)?;
+        let mut nodes = vec![*coord];
+        while let Some(previous) = self.predecessors.get(nodes.last().unwrap()).unwrap() {
+            nodes.push(*previous);
+        }
+        nodes.reverse();
+
+        Some(Path { nodes, cost })
+    }
+
+    fn reach(&mut self, coord: &Coord<U>, distance: C, from: Option<&Coord<U>>, source: usize) {
+        *self.distances.get_mut(coord).unwrap() = Some(distance);
+        *self.predecessors.get_mut(coord).unwrap() = from.copied();
+        *self.nearest_sources.get_mut(coord).unwrap() = Some(source);
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    /// Number of steps from the nearest of `sources` to every cell, moving only
+    /// onto `passable` cells.
+    pub fn distances_from(
+        &self,
+        sources: impl IntoIterator<Item = Coord<U>>,
+        passable: impl Fn(&N) -> bool,
+    ) -> Grid<Option<u32>, U> {
+        self.distance_map(sources, passable).distances
+    }
+
+    /// Breadth-first search from all `sources` at once, moving only onto
+    /// `passable` cells.
+    pub fn distance_map(
+        &self,
+        sources: impl IntoIterator<Item = Coord<U>>,
+        passable: impl Fn(&N) -> bool,
+    ) -> DistanceMap<u32, U> {
+        let mut result = DistanceMap::new(self);
+        let mut queue = VecDeque::new();
+
+        for (index, source) in sources.into_iter().enumerate() {
+            if result.distances.get(&source) == Some(&None) {
+                result.reach(&source, 0, None, index);
+                queue.push_back(source);
+            }
+        }
+
+        while let Some(coord) = queue.pop_front() {
+            let distance = result.distance(&coord).unwrap();
+            let source = result.nearest_source(&coord).unwrap();
+            for (neighbour, node) in self.neighbours(&coord, &Neighbourhood::VonNeumann) {
+                if result.distance(&neighbour).is_none() && passable(node) {
+                    result.reach(&neighbour, distance + 1, Some(&coord), source);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Dijkstra from all `sources` at once: entering a cell costs `cost(cell)`,
+    /// `None` meaning the cell cannot be entered.
+    pub fn weighted_distance_map<C>(
+        &self,
+        sources: impl IntoIterator<Item = Coord<U>>,
+        cost: impl Fn(&N) -> Option<C>,
+    ) -> DistanceMap<C, U>
+    where
+        C: Cost,
+    {
+        let mut result = DistanceMap::new(self);
+        let mut queue = BinaryHeap::new();
+
+        for (index, source) in sources.into_iter().enumerate() {
+            if result.distances.get(&source) == Some(&None) {
+                result.reach(&source, C::default(), None, index);
+                queue.push(Reverse((C::default(), source)));
+            }
+        }
+
+        while let Some(Reverse((distance, coord))) = queue.pop() {
+            if result.distance(&coord) != Some(distance) {
+                continue;
+            }
+
+            let source = result.nearest_source(&coord).unwrap();
+            for (neighbour, node) in self.neighbours(&coord, &Neighbourhood::VonNeumann) {
+                let Some(cost) = cost(node) else {
+                    continue;
+                };
+                let distance = distance + cost;
+                if result
+                    .distance(&neighbour)
+                    .is_none_or(|existing| distance < existing)
+                {
+                    result.reach(&neighbour, distance, Some(&coord), source);
+                    queue.push(Reverse((distance, neighbour)));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn maze() -> Grid<char> {
+        "S..#\n.#.#\n...E\n".parse().unwrap()
+    }
+
+    #[test]
+    fn distances_from_one_source() {
+        let distances = maze().distances_from([Coord { x: 0, y: 0 }], |&c| c != '#');
+
+        assert_eq!(distances.get(&Coord { x: 3, y: 2 }), Some(&Some(5)));
+        assert_eq!(distances.get(&Coord { x: 2, y: 1 }), Some(&Some(3)));
+        assert_eq!(distances.get(&Coord { x: 3, y: 0 }), Some(&None));
+    }
+
+    #[test]
+    fn path_to_any_cell() {
+        let map = maze().distance_map([Coord { x: 0, y: 0 }], |&c| c != '#');
+        let path = map.path_to(&Coord { x: 2, y: 1 }).unwrap();
+
+        assert_eq!(path.cost, 3);
+        assert_eq!(
+            path.nodes,
+            vec![
+                Coord { x: 0, y: 0 },
+                Coord { x: 1, y: 0 },
+                Coord { x: 2, y: 0 },
+                Coord { x: 2, y: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_sources_split_the_grid() {
+        let grid: Grid<char> = ".....\n".parse().unwrap();
+        let map = grid.distance_map([Coord { x: 0, y: 0 }, Coord { x: 4, y: 0 }], |_| true);
+
+        let labels: Vec<_> = map
+            .nearest_sources
+            .nodes()
+            .iter()
+            .map(|s| s.unwrap())
+            .collect();
+        assert_eq!(labels, vec![0, 0, 0, 1, 1]);
+        assert_eq!(map.distance(&Coord { x: 3, y: 0 }), Some(1));
+    }
+
+    #[test]
+    fn weighted_distances_prefer_cheap_cells() {
+        let grid: Grid<u8> = Grid::from([[1, 9, 1], [1, 1, 1]]);
+        let map = grid.weighted_distance_map([Coord { x: 0, y: 0 }], |&cost| Some(cost as u32));
+
+        assert_eq!(map.distance(&Coord { x: 2, y: 0 }), Some(4));
+        assert_eq!(
+            map.path_to(&Coord { x: 2, y: 0 }).unwrap().nodes,
+            vec![
+                Coord { x: 0, y: 0 },
+                Coord { x: 0, y: 1 },
+                Coord { x: 1, y: 1 },
+                Coord { x: 2, y: 1 },
+                Coord { x: 2, y: 0 },
+            ]
+        );
+    }
+}
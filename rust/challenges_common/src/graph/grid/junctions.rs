@@ -0,0 +1,225 @@
+use crate::graph::{Coord, Grid, Neighbourhood, Path, Step};
+use num_traits::PrimInt;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Maze contracted by [`Grid::junction_graph`]: its nodes are the junctions,
+/// dead ends and points of interest, linked by the corridors between them.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph<U = usize> {
+    corridors: HashMap<Coord<U>, Vec<Corridor<U>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corridor<U = usize> {
+    pub to: Coord<U>,
+    /// Number of moves along the corridor.
+    pub length: u32,
+    /// Every cell of the corridor, both ends included.
+    pub cells: Vec<Coord<U>>,
+}
+
+impl<U> JunctionGraph<U>
+where
+    U: PrimInt + Hash,
+{
+    pub fn nodes(&self) -> impl Iterator<Item = &Coord<U>> + '_ {
+        self.corridors.keys()
+    }
+
+    pub fn contains(&self, coord: &Coord<U>) -> bool {
+        self.corridors.contains_key(coord)
+    }
+
+    /// Corridors leaving `from`, empty if it is not a node.
+    pub fn corridors(&self, from: &Coord<U>) -> &[Corridor<U>] {
+        self.corridors.get(from).map_or(&[], Vec::as_slice)
+    }
+
+    /// Corridors leaving `from` as [`Step`]s, to search the graph with
+    /// [`crate::graph::astar`].
+    pub fn steps(&self, from: &Coord<U>) -> impl Iterator<Item = Step<Coord<U>, u32>> + '_ {
+        self.corridors(from).iter().map(|corridor| Step {
+            to: corridor.to,
+            additional_cost: corridor.length,
+        })
+    }
+
+    /// Turns a path between nodes into the path through every grid cell,
+    /// following the shortest corridor between consecutive nodes. `None` when
+    /// two consecutive nodes are not linked.
+    pub fn expand<C: Copy>(&self, path: &Path<Coord<U>, C>) -> Option<Path<Coord<U>, C>> {
+        let mut nodes = path.nodes.first().copied().into_iter().collect::<Vec<_>>();
+        for window in path.nodes.windows(2) {
+            let corridor = self
+                .corridors(&window[0])
+                .iter()
+                .filter(|corridor| corridor.to == window[1])
+                .min_by_key(|corridor| corridor.length)?;
+            nodes.extend(corridor.cells.iter().skip(1));
+        }
+
+        Some(Path {
+            nodes,
+            cost: path.cost,
+        })
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt + Hash,
+{
+    /// Contracts the maze made of the `passable` cells: every passable cell
+    /// without exactly 2 passable neighbours, and every point of interest,
+    /// becomes a node. A corridor coming back to the node it left is kept as a
+    /// self-loop, once in each direction. Cycles without any node are dropped.
+    pub fn junction_graph(
+        &self,
+        passable: impl Fn(&N) -> bool,
+        points_of_interest: impl IntoIterator<Item = Coord<U>>,
+    ) -> JunctionGraph<U> {
+        let open_neighbours = |coord: &Coord<U>| {
+            self.neighbours(coord, &Neighbourhood::VonNeumann)
+                .filter(|(_, node)| passable(node))
+                .map(|(coord, _)| coord)
+                .collect::<Vec<_>>()
+        };
+
+        let mut nodes: HashSet<_> = points_of_interest
+            .into_iter()
+            .filter(|coord| self.get(coord).is_some_and(&passable))
+            .collect();
+        nodes.extend(
+            self.entries()
+                .filter(|(coord, node)| passable(node) && open_neighbours(coord).len() != 2)
+                .map(|(coord, _)| coord),
+        );
+
+        let corridors = nodes
+            .iter()
+            .map(|&start| {
+                let corridors = open_neighbours(&start)
+                    .into_iter()
+                    .filter_map(|first| {
+                        let mut cells = vec![start, first];
+                        while !nodes.contains(cells.last().unwrap()) {
+                            let [.., previous, current] = cells[..] else {
+                                unreachable!()
+                            };
+                            let next = open_neighbours(&current)
+                                .into_iter()
+                                .find(|next| *next != previous)?;
+                            cells.push(next);
+                        }
+
+                        Some(Corridor {
+                            to: *cells.last().unwrap(),
+                            length: cells.len() as u32 - 1,
+                            cells,
+                        })
+                    })
+                    .collect();
+                (start, corridors)
+            })
+            .collect();
+
+        JunctionGraph { corridors }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::astar;
+
+    fn maze() -> Grid<char> {
+        [
+            "#########",
+            "#S..#...#",
+            "##.##.#.#",
+            "##.....E#",
+            "#########",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn nodes_are_junctions_dead_ends_and_points_of_interest() {
+        let maze = maze();
+        let start = maze.find(|&c| c == 'S').unwrap();
+        let end = maze.find(|&c| c == 'E').unwrap();
+        let graph = maze.junction_graph(|&c| c != '#', [start, end]);
+
+        let mut nodes: Vec<_> = graph.nodes().copied().collect();
+        nodes.sort_by_key(|coord| (coord.y, coord.x));
+        assert_eq!(
+            nodes,
+            vec![
+                start,
+                Coord { x: 2, y: 1 },
+                Coord { x: 3, y: 1 },
+                Coord { x: 5, y: 3 },
+                Coord { x: 7, y: 3 },
+            ]
+        );
+        assert_eq!(graph.corridors(&Coord { x: 5, y: 3 }).len(), 3);
+        assert!(graph.corridors(&Coord { x: 3, y: 3 }).is_empty());
+    }
+
+    #[test]
+    fn loops_back_to_a_node_are_self_loops() {
+        let maze: Grid<char> = ["#######", "#S....#", "###.#.#", "###...#", "#######"]
+            .join("\n")
+            .parse()
+            .unwrap();
+        let start = maze.find(|&c| c == 'S').unwrap();
+        let junction = Coord { x: 3, y: 1 };
+        let graph = maze.junction_graph(|&c| c != '#', [start]);
+
+        let loops: Vec<_> = graph
+            .corridors(&junction)
+            .iter()
+            .filter(|corridor| corridor.to == junction)
+            .collect();
+        assert_eq!(loops.len(), 2);
+        assert!(loops.iter().all(|corridor| corridor.length == 8
+            && corridor.cells.first() == Some(&junction)
+            && corridor.cells.len() == 9));
+        assert_eq!(
+            loops[0].cells.iter().rev().collect::<Vec<_>>(),
+            loops[1].cells.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(graph.corridors(&start).len(), 1);
+    }
+
+    #[test]
+    fn expanded_path_matches_the_grid_one() {
+        let maze = maze();
+        let start = maze.find(|&c| c == 'S').unwrap();
+        let end = maze.find(|&c| c == 'E').unwrap();
+        let graph = maze.junction_graph(|&c| c != '#', [start, end]);
+
+        let compressed = astar(
+            start,
+            |coord| graph.steps(coord),
+            |coord| coord == &end,
+            |_| 0,
+        )
+        .unwrap();
+        let expanded = graph.expand(&compressed).unwrap();
+        let direct = maze
+            .shortest_path(start, end, |_, &to, _| (to != '#').then_some(1u32))
+            .unwrap();
+
+        assert_eq!(compressed.nodes.len(), 4);
+        assert_eq!(expanded.cost, direct.cost);
+        assert_eq!(expanded.nodes.len() as u32, direct.cost + 1);
+        assert!(expanded
+            .nodes
+            .windows(2)
+            .all(|pair| pair[0].manhattan_dist_to(&pair[1]) == 1));
+    }
+}
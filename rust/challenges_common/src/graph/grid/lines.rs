@@ -0,0 +1,380 @@
+use crate::graph::{Coord, Grid};
+use num_traits::PrimInt;
+
+/// Read-only straight line of cells (row, column or diagonal) of a [`Grid`].
+#[derive(Debug)]
+pub struct Line<'a, N, U = usize> {
+    grid: &'a Grid<N, U>,
+    start: (usize, usize),
+    step: (isize, isize),
+    len: usize,
+}
+
+impl<N, U> Clone for Line<'_, N, U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N, U> Copy for Line<'_, N, U> {}
+
+impl<'a, N, U> Line<'a, N, U>
+where
+    U: PrimInt,
+{
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// `(dx, dy)` between two consecutive cells.
+    pub fn step(&self) -> (isize, isize) {
+        self.step
+    }
+
+    pub fn coord(&self, index: usize) -> Option<Coord<U>> {
+        (index < self.len).then(|| {
+            let (x, y) = self.position(index);
+            Coord {
+                x: U::from(x).unwrap(),
+                y: U::from(y).unwrap(),
+            }
+        })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a N> {
+        self.grid.get(&self.coord(index)?)
+    }
+
+    pub fn coords(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Coord<U>> + ExactSizeIterator + use<'a, N, U> {
+        let line = *self;
+        (0..self.len).map(move |index| line.coord(index).unwrap())
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &'a N> + ExactSizeIterator + use<'a, N, U> {
+        let line = *self;
+        (0..self.len).map(move |index| line.get(index).unwrap())
+    }
+
+    pub fn entries(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (Coord<U>, &'a N)> + ExactSizeIterator + use<'a, N, U>
+    {
+        let line = *self;
+        (0..self.len).map(move |index| (line.coord(index).unwrap(), line.get(index).unwrap()))
+    }
+
+    fn position(&self, index: usize) -> (usize, usize) {
+        let offset = |start: usize, step: isize| (start as isize + step * index as isize) as usize;
+        (
+            offset(self.start.0, self.step.0),
+            offset(self.start.1, self.step.1),
+        )
+    }
+}
+
+/// Mutable counterpart of [`Line`].
+#[derive(Debug)]
+pub struct LineMut<'a, N, U = usize> {
+    cells: Vec<(Coord<U>, &'a mut N)>,
+}
+
+impl<'a, N, U> LineMut<'a, N, U>
+where
+    U: PrimInt,
+{
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn coord(&self, index: usize) -> Option<Coord<U>> {
+        self.cells.get(index).map(|(coord, _)| *coord)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&N> {
+        self.cells.get(index).map(|(_, node)| &**node)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut N> {
+        self.cells.get_mut(index).map(|(_, node)| &mut **node)
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a != b {
+            let (low, high) = (a.min(b), a.max(b));
+            let (left, right) = self.cells.split_at_mut(high);
+            std::mem::swap(left[low].1, right[0].1);
+        }
+    }
+
+    pub fn coords(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Coord<U>> + ExactSizeIterator + use<'_, 'a, N, U> {
+        self.cells.iter().map(|(coord, _)| *coord)
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &N> + ExactSizeIterator + use<'_, 'a, N, U> {
+        self.cells.iter().map(|(_, node)| &**node)
+    }
+
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut N> + ExactSizeIterator + use<'_, 'a, N, U> {
+        self.cells.iter_mut().map(|(_, node)| &mut **node)
+    }
+
+    pub fn entries_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (Coord<U>, &mut N)> + ExactSizeIterator + use<'_, 'a, N, U>
+    {
+        self.cells
+            .iter_mut()
+            .map(|(coord, node)| (*coord, &mut **node))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum LineKind {
+    Row,
+    Column,
+    /// Top-left to bottom-right.
+    Diagonal,
+    /// Top-right to bottom-left.
+    AntiDiagonal,
+}
+
+impl LineKind {
+    fn step(&self) -> (isize, isize) {
+        match self {
+            LineKind::Row => (1, 0),
+            LineKind::Column => (0, 1),
+            LineKind::Diagonal => (1, 1),
+            LineKind::AntiDiagonal => (-1, 1),
+        }
+    }
+
+    fn count(&self, width: usize, height: usize) -> usize {
+        match self {
+            _ if width == 0 || height == 0 => 0,
+            LineKind::Row => height,
+            LineKind::Column => width,
+            LineKind::Diagonal | LineKind::AntiDiagonal => width + height - 1,
+        }
+    }
+
+    /// `(start, len)` of the `index`th line.
+    fn line(&self, index: usize, width: usize, height: usize) -> ((usize, usize), usize) {
+        match self {
+            LineKind::Row => ((0, index), width),
+            LineKind::Column => ((index, 0), height),
+            LineKind::Diagonal => {
+                let (x, y) = if index < height {
+                    (0, height - 1 - index)
+                } else {
+                    (index - (height - 1), 0)
+                };
+                ((x, y), (width - x).min(height - y))
+            }
+            LineKind::AntiDiagonal => {
+                let x = index.min(width - 1);
+                let y = index - x;
+                ((x, y), (x + 1).min(height - y))
+            }
+        }
+    }
+
+    /// Index of the line going through `(x, y)`.
+    fn index_of(&self, (x, y): (usize, usize), height: usize) -> usize {
+        match self {
+            LineKind::Row => y,
+            LineKind::Column => x,
+            LineKind::Diagonal => x + height - 1 - y,
+            LineKind::AntiDiagonal => x + y,
+        }
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    pub fn row(&self, y: usize) -> Option<Line<'_, N, U>> {
+        (y < self.height().to_usize().unwrap()).then(|| self.line(LineKind::Row, y))
+    }
+
+    pub fn column(&self, x: usize) -> Option<Line<'_, N, U>> {
+        (x < self.width().to_usize().unwrap()).then(|| self.line(LineKind::Column, x))
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = Line<'_, N, U>> + ExactSizeIterator {
+        self.lines(LineKind::Row)
+    }
+
+    pub fn columns(&self) -> impl DoubleEndedIterator<Item = Line<'_, N, U>> + ExactSizeIterator {
+        self.lines(LineKind::Column)
+    }
+
+    /// Lines going down-right, from the bottom-left corner to the top-right one.
+    pub fn diagonals(&self) -> impl DoubleEndedIterator<Item = Line<'_, N, U>> + ExactSizeIterator {
+        self.lines(LineKind::Diagonal)
+    }
+
+    /// Lines going down-left, from the top-left corner to the bottom-right one.
+    pub fn anti_diagonals(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Line<'_, N, U>> + ExactSizeIterator {
+        self.lines(LineKind::AntiDiagonal)
+    }
+
+    pub fn rows_mut(&mut self) -> impl DoubleEndedIterator<Item = LineMut<'_, N, U>> {
+        self.lines_mut(LineKind::Row)
+    }
+
+    pub fn columns_mut(&mut self) -> impl DoubleEndedIterator<Item = LineMut<'_, N, U>> {
+        self.lines_mut(LineKind::Column)
+    }
+
+    pub fn diagonals_mut(&mut self) -> impl DoubleEndedIterator<Item = LineMut<'_, N, U>> {
+        self.lines_mut(LineKind::Diagonal)
+    }
+
+    pub fn anti_diagonals_mut(&mut self) -> impl DoubleEndedIterator<Item = LineMut<'_, N, U>> {
+        self.lines_mut(LineKind::AntiDiagonal)
+    }
+
+    fn lines(
+        &self,
+        kind: LineKind,
+    ) -> impl DoubleEndedIterator<Item = Line<'_, N, U>> + ExactSizeIterator {
+        let (width, height) = self.usize_dimensions();
+        (0..kind.count(width, height)).map(move |index| self.line(kind, index))
+    }
+
+    fn line(&self, kind: LineKind, index: usize) -> Line<'_, N, U> {
+        let (width, height) = self.usize_dimensions();
+        let (start, len) = kind.line(index, width, height);
+        Line {
+            grid: self,
+            start,
+            step: kind.step(),
+            len,
+        }
+    }
+
+    fn lines_mut(&mut self, kind: LineKind) -> impl DoubleEndedIterator<Item = LineMut<'_, N, U>> {
+        let (width, height) = self.usize_dimensions();
+        let mut lines: Vec<_> = (0..kind.count(width, height))
+            .map(|index| LineMut {
+                cells: Vec::with_capacity(kind.line(index, width, height).1),
+            })
+            .collect();
+
+        // every line is walked with a growing (or constant, for rows) y, so the
+        // row-major order of the content is also the order inside each line
+        for (i, node) in self.content.iter_mut().enumerate() {
+            let (x, y) = (i % width, i / width);
+            let coord = Coord {
+                x: U::from(x).unwrap(),
+                y: U::from(y).unwrap(),
+            };
+            lines[kind.index_of((x, y), height)]
+                .cells
+                .push((coord, node));
+        }
+
+        lines.into_iter()
+    }
+
+    fn usize_dimensions(&self) -> (usize, usize) {
+        (
+            self.width().to_usize().unwrap(),
+            self.height().to_usize().unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::SparseGrid;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    fn lines_to_strings<'a>(lines: impl Iterator<Item = Line<'a, char>>) -> Vec<String> {
+        lines.map(|line| line.iter().collect()).collect()
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+        assert_eq!(lines_to_strings(grid.rows()), vec!["abc", "def"]);
+        assert_eq!(lines_to_strings(grid.columns()), vec!["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn empty_grid_has_no_lines() {
+        let mut grid = SparseGrid::<char, usize>::new('.').to_grid();
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.columns().count(), 0);
+        assert_eq!(grid.diagonals().count(), 0);
+        assert_eq!(grid.anti_diagonals_mut().count(), 0);
+        assert!(grid.row(0).is_none());
+    }
+
+    #[test]
+    fn diagonals() {
+        let grid = grid();
+        assert_eq!(
+            lines_to_strings(grid.diagonals()),
+            vec!["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            lines_to_strings(grid.anti_diagonals()),
+            vec!["a", "bd", "ce", "f"]
+        );
+    }
+
+    #[test]
+    fn lines_iterate_backward_with_coords() {
+        let grid = grid();
+        let diagonal = grid.diagonals().nth(1).unwrap();
+
+        assert_eq!(
+            diagonal.entries().rev().collect::<Vec<_>>(),
+            vec![(Coord { x: 1, y: 1 }, &'e'), (Coord { x: 0, y: 0 }, &'a')]
+        );
+        assert_eq!(grid.column(2).unwrap().get(1), Some(&'f'));
+        assert_eq!(grid.row(2).map(|row| row.len()), None);
+    }
+
+    #[test]
+    fn mutable_lines_write_through() {
+        let mut grid = grid();
+        for mut column in grid.columns_mut() {
+            column.swap(0, 1);
+        }
+        assert_eq!(grid.to_string(), "def\nabc\n");
+
+        for mut anti_diagonal in grid.anti_diagonals_mut() {
+            if let Some(node) = anti_diagonal.get_mut(0) {
+                *node = node.to_ascii_uppercase();
+            }
+        }
+        assert_eq!(grid.to_string(), "DEF\nabC\n");
+    }
+}
@@ -0,0 +1,316 @@
+use crate::graph::{Coord, Direction, Grid, Turn, Vec2};
+use num_traits::{PrimInt, Signed};
+
+/// One of the 8 directions around a cell, `N` being [`Direction::Up`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// Clockwise, starting from `N`.
+    pub fn all() -> [Direction8; 8] {
+        use Direction8::*;
+        [N, NE, E, SE, S, SW, W, NW]
+    }
+
+    /// Quarter turn, like [`Direction::turn`].
+    pub fn turn(&self, turn: Turn) -> Self {
+        self.rotate(turn, 2)
+    }
+
+    /// Eighth of a turn.
+    pub fn turn_45(&self, turn: Turn) -> Self {
+        self.rotate(turn, 1)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(Turn::Right, 4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    fn rotate(&self, turn: Turn, eighths: usize) -> Self {
+        let index = match turn {
+            Turn::Right => self.index() + eighths,
+            Turn::Left => self.index() + 8 - eighths,
+        };
+        Self::all()[index % 8]
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction8::N => (0, -1),
+            Direction8::NE => (1, -1),
+            Direction8::E => (1, 0),
+            Direction8::SE => (1, 1),
+            Direction8::S => (0, 1),
+            Direction8::SW => (-1, 1),
+            Direction8::W => (-1, 0),
+            Direction8::NW => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Direction8::N,
+            Direction::Down => Direction8::S,
+            Direction::Left => Direction8::W,
+            Direction::Right => Direction8::E,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    fn try_from(direction: Direction8) -> Result<Self, Self::Error> {
+        match direction {
+            Direction8::N => Ok(Direction::Up),
+            Direction8::S => Ok(Direction::Down),
+            Direction8::W => Ok(Direction::Left),
+            Direction8::E => Ok(Direction::Right),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+impl<U> From<Direction8> for Vec2<U>
+where
+    U: PrimInt + Signed,
+{
+    fn from(direction: Direction8) -> Self {
+        let (x, y) = direction.offset();
+        Vec2 {
+            x: U::from(x).unwrap(),
+            y: U::from(y).unwrap(),
+        }
+    }
+}
+
+/// Which cells are the neighbours of a cell.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Neighbourhood {
+    /// The 4 orthogonal cells.
+    VonNeumann,
+    /// The 8 surrounding cells, diagonals included.
+    Moore,
+    /// Any set of offsets, e.g. knight moves.
+    Custom(Vec<Vec2<isize>>),
+}
+
+const VON_NEUMANN: [Vec2<isize>; 4] = [
+    Vec2 { x: 0, y: -1 },
+    Vec2 { x: 0, y: 1 },
+    Vec2 { x: -1, y: 0 },
+    Vec2 { x: 1, y: 0 },
+];
+
+const MOORE: [Vec2<isize>; 8] = [
+    Vec2 { x: 0, y: -1 },
+    Vec2 { x: 0, y: 1 },
+    Vec2 { x: -1, y: 0 },
+    Vec2 { x: 1, y: 0 },
+    Vec2 { x: -1, y: -1 },
+    Vec2 { x: 1, y: -1 },
+    Vec2 { x: -1, y: 1 },
+    Vec2 { x: 1, y: 1 },
+];
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &[Vec2<isize>] {
+        match self {
+            Neighbourhood::VonNeumann => &VON_NEUMANN,
+            Neighbourhood::Moore => &MOORE,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+impl<U: PrimInt> Coord<U> {
+    /// Coordinate moved by `offset`, or `None` when it does not fit in `U`.
+    pub fn try_at_offset(&self, offset: &Vec2<isize>) -> Option<Self> {
+        let shift = |value: U, delta: isize| {
+            let magnitude = U::from(delta.unsigned_abs())?;
+            if delta < 0 {
+                value.checked_sub(&magnitude)
+            } else {
+                value.checked_add(&magnitude)
+            }
+        };
+
+        Some(Coord {
+            x: shift(self.x, offset.x)?,
+            y: shift(self.y, offset.y)?,
+        })
+    }
+
+    pub fn try_at8(&self, dir: Direction8) -> Option<Self> {
+        self.try_at_offset(&dir.into())
+    }
+
+    pub fn neighbours<'a>(
+        &self,
+        neighbourhood: &'a Neighbourhood,
+    ) -> impl Iterator<Item = Self> + 'a
+    where
+        U: 'a,
+    {
+        let coord = *self;
+        neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |offset| coord.try_at_offset(offset))
+    }
+
+    /// The 8 surrounding cells, clockwise from `N`, with the direction leading
+    /// to each of them.
+    pub fn neighbours_with_dir(&self) -> impl Iterator<Item = (Direction8, Self)> {
+        let coord = *self;
+        Direction8::all()
+            .into_iter()
+            .filter_map(move |dir| Some((dir, coord.try_at8(dir)?)))
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    /// Coordinate moved by `offset`, horizontally then vertically, wrapped
+    /// according to the topology.
+    pub fn try_at_offset(&self, coord: &Coord<U>, offset: &Vec2<isize>) -> Option<Coord<U>> {
+        let mut result = *coord;
+        for (delta, backward, forward) in [
+            (offset.x, Direction::Left, Direction::Right),
+            (offset.y, Direction::Up, Direction::Down),
+        ] {
+            if delta != 0 {
+                let dir = if delta < 0 { backward } else { forward };
+                result = self.try_at_dist(&result, dir, U::from(delta.unsigned_abs())?)?;
+            }
+        }
+
+        self.is_coord_inside(&result).then_some(result)
+    }
+
+    pub fn try_at8(&self, coord: &Coord<U>, dir: Direction8) -> Option<Coord<U>> {
+        self.try_at_offset(coord, &dir.into())
+    }
+
+    /// Distinct neighbours of `coord`: on a wrapping axis only 1 or 2 cells
+    /// wide, several offsets lead to the same cell or back to `coord`.
+    pub fn neighbours<'a>(
+        &'a self,
+        coord: &Coord<U>,
+        neighbourhood: &'a Neighbourhood,
+    ) -> impl Iterator<Item = (Coord<U>, &'a N)> + 'a {
+        let coord = *coord;
+        let mut seen = vec![coord];
+        neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |offset| self.try_at_offset(&coord, offset))
+            .filter(move |neighbour| {
+                let new = !seen.contains(neighbour);
+                if new {
+                    seen.push(*neighbour);
+                }
+                new
+            })
+            .map(|coord| (coord, self.get(&coord).unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Topology;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction8::N.turn(Turn::Right), Direction8::E);
+        assert_eq!(Direction8::NE.turn(Turn::Left), Direction8::NW);
+        assert_eq!(Direction8::NW.turn_45(Turn::Right), Direction8::N);
+        assert_eq!(Direction8::SW.opposite(), Direction8::NE);
+        assert!(Direction8::all()
+            .iter()
+            .all(|dir| dir.turn_45(Turn::Left).turn_45(Turn::Right) == *dir));
+    }
+
+    #[test]
+    fn conversions() {
+        for dir in Direction::all() {
+            assert_eq!(Vec2::<i32>::from(Direction8::from(dir)), Vec2::from(dir));
+            assert_eq!(Direction::try_from(Direction8::from(dir)), Ok(dir));
+        }
+        assert_eq!(Direction::try_from(Direction8::SE), Err(Direction8::SE));
+        assert_eq!(Vec2::<i64>::from(Direction8::SW), Vec2::new(-1, 1));
+    }
+
+    #[test]
+    fn neighbours_with_dir_skip_negative_coords() {
+        let dirs: Vec<_> = Coord { x: 0usize, y: 3 }
+            .neighbours_with_dir()
+            .map(|(dir, _)| dir)
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                Direction8::N,
+                Direction8::NE,
+                Direction8::E,
+                Direction8::SE,
+                Direction8::S
+            ]
+        );
+        assert_eq!(
+            Coord { x: 1, y: 1 }.try_at8(Direction8::NW),
+            Some(Coord { x: 0, y: 0 })
+        );
+    }
+
+    #[test]
+    fn custom_neighbourhood() {
+        let knight =
+            Neighbourhood::Custom(vec![Vec2::new(1, 2), Vec2::new(-1, 2), Vec2::new(2, -1)]);
+        let coord = Coord { x: 0usize, y: 0 };
+        assert_eq!(
+            coord.neighbours(&knight).collect::<Vec<_>>(),
+            vec![Coord { x: 1, y: 2 }]
+        );
+    }
+
+    #[test]
+    fn grid_moore_neighbours_wrap() {
+        let grid: Grid<char> = "abc\ndef\nghi\n".parse().unwrap();
+        assert_eq!(
+            grid.neighbours(&Coord { x: 0, y: 0 }, &Neighbourhood::Moore)
+                .count(),
+            3
+        );
+
+        let grid = grid.with_topology(Topology::Toroidal);
+        let mut neighbours: Vec<_> = grid
+            .neighbours(&Coord { x: 0, y: 0 }, &Neighbourhood::Moore)
+            .map(|(_, &c)| c)
+            .collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!['b', 'c', 'd', 'e', 'f', 'g', 'h', 'i']);
+    }
+}
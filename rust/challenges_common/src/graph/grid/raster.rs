@@ -0,0 +1,160 @@
+use crate::graph::{Coord, Grid, SparseGrid};
+use itertools::Itertools;
+use num_traits::PrimInt;
+use std::hash::Hash;
+use std::iter::from_fn;
+
+/// Cells from `from` to `to`, both included, when they are on the same row,
+/// column or 45° diagonal.
+pub fn segment<U: PrimInt>(from: Coord<U>, to: Coord<U>) -> Option<impl Iterator<Item = Coord<U>>> {
+    let (dx, dy) = deltas(&from, &to);
+    (dx == 0 || dy == 0 || dx.abs() == dy.abs()).then(|| bresenham(from, to))
+}
+
+/// Cells of the Bresenham line from `from` to `to`, both included.
+pub fn bresenham<U: PrimInt>(from: Coord<U>, to: Coord<U>) -> impl Iterator<Item = Coord<U>> {
+    let (dx, dy) = deltas(&from, &to);
+    let (step_x, step_y) = (dx.signum(), dy.signum());
+    let (dx, dy) = (dx.abs(), -dy.abs());
+    let mut error = dx + dy;
+
+    let mut remaining = dx.max(-dy) + 1;
+    let mut current = from;
+    from_fn(move || {
+        if remaining == 0 {
+            return None;
+        }
+        remaining -= 1;
+
+        let result = current;
+        if remaining == 0 {
+            return Some(result);
+        }
+
+        let double_error = 2 * error;
+        if double_error >= dy {
+            error += dy;
+            current.x = shift(current.x, step_x);
+        }
+        if double_error <= dx {
+            error += dx;
+            current.y = shift(current.y, step_y);
+        }
+        Some(result)
+    })
+}
+
+/// Cells of the segments joining consecutive `points`, each joint only once.
+pub fn polyline<U: PrimInt>(
+    points: impl IntoIterator<Item = Coord<U>>,
+) -> impl Iterator<Item = Coord<U>> {
+    let mut points = points.into_iter().peekable();
+    let first = points.peek().copied();
+    first.into_iter().chain(
+        points
+            .tuple_windows()
+            .flat_map(|(from, to)| bresenham(from, to).skip(1)),
+    )
+}
+
+fn deltas<U: PrimInt>(from: &Coord<U>, to: &Coord<U>) -> (i64, i64) {
+    let signed = |from: U, to: U| to.to_i64().unwrap() - from.to_i64().unwrap();
+    (signed(from.x, to.x), signed(from.y, to.y))
+}
+
+fn shift<U: PrimInt>(value: U, step: i64) -> U {
+    match step {
+        1 => value + U::one(),
+        -1 => value - U::one(),
+        _ => value,
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    /// Sets `value` on every cell of `shape`, ignoring those outside the grid.
+    pub fn fill(&mut self, shape: impl IntoIterator<Item = Coord<U>>, value: N)
+    where
+        N: Clone,
+    {
+        for coord in shape {
+            if let Some(node) = self.get_mut(&coord) {
+                *node = value.clone();
+            }
+        }
+    }
+}
+
+impl<N, U> SparseGrid<N, U>
+where
+    U: PrimInt + Hash,
+{
+    pub fn fill(&mut self, shape: impl IntoIterator<Item = Coord<U>>, value: N)
+    where
+        N: Clone,
+    {
+        for coord in shape {
+            self.insert(coord, value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn coords(coords: &[(i64, i64)]) -> Vec<Coord<i64>> {
+        coords.iter().map(|&(x, y)| Coord { x, y }).collect()
+    }
+
+    #[test]
+    fn straight_segments() {
+        let horizontal: Vec<_> = segment(Coord { x: 3, y: 1 }, Coord { x: 1, y: 1 })
+            .unwrap()
+            .collect();
+        assert_eq!(horizontal, coords(&[(3, 1), (2, 1), (1, 1)]));
+
+        let diagonal: Vec<_> = segment(Coord { x: 0, y: 2 }, Coord { x: 2, y: 0 })
+            .unwrap()
+            .collect();
+        assert_eq!(diagonal, coords(&[(0, 2), (1, 1), (2, 0)]));
+
+        assert!(segment(Coord { x: 0, y: 0 }, Coord { x: 2, y: 1 }).is_none());
+    }
+
+    #[test]
+    fn bresenham_line() {
+        let line: Vec<_> = bresenham(Coord { x: 0, y: 0 }, Coord { x: 5, y: -2 }).collect();
+        assert_eq!(
+            line,
+            coords(&[(0, 0), (1, 0), (2, -1), (3, -1), (4, -2), (5, -2)])
+        );
+
+        let point: Vec<_> = bresenham(Coord { x: 1usize, y: 1 }, Coord { x: 1, y: 1 }).collect();
+        assert_eq!(point, vec![Coord { x: 1, y: 1 }]);
+
+        let towards_origin: Vec<_> =
+            bresenham(Coord { x: 2usize, y: 1 }, Coord { x: 0, y: 0 }).collect();
+        assert_eq!(towards_origin.last(), Some(&Coord { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn polyline_joints_are_not_repeated() {
+        let path: Vec<_> = polyline(coords(&[(0, 0), (0, 2), (2, 2)])).collect();
+        assert_eq!(path, coords(&[(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]));
+        assert_eq!(polyline(coords(&[(4, 4)])).count(), 1);
+    }
+
+    #[test]
+    fn fill_shapes() {
+        let mut grid: Grid<char> = "...\n...\n".parse().unwrap();
+        grid.fill(bresenham(Coord { x: 0, y: 0 }, Coord { x: 4, y: 0 }), '#');
+        assert_eq!(grid.to_string(), "###\n...\n");
+
+        let mut sparse = SparseGrid::<char>::new('.');
+        sparse.fill(polyline(coords(&[(0, 0), (0, 1), (1, 1)])), '#');
+        assert_eq!(sparse.to_string(), "#.\n##\n");
+    }
+}
@@ -0,0 +1,263 @@
+use crate::geom::Rect;
+use crate::graph::{Coord, Direction, Grid, Path};
+use num_traits::PrimInt;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write};
+use std::hash::Hash;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+        }
+    }
+}
+
+/// What is drawn on a cell by an overlay.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Mark {
+    pub char: char,
+    pub colour: Option<Colour>,
+}
+
+impl Mark {
+    pub fn coloured(self, colour: Colour) -> Self {
+        Self {
+            colour: Some(colour),
+            ..self
+        }
+    }
+}
+
+impl From<char> for Mark {
+    fn from(char: char) -> Self {
+        Self { char, colour: None }
+    }
+}
+
+/// Debug drawing of a [`Grid`]: every cell goes through `to_char`, then the
+/// overlays are drawn on top of it, the last added being the topmost.
+pub struct GridRenderer<'a, N, U = usize> {
+    grid: &'a Grid<N, U>,
+    to_char: Box<dyn Fn(&N) -> char + 'a>,
+    overlays: Vec<HashMap<Coord<U>, Mark>>,
+    coloured: bool,
+    viewport: Option<Rect<U>>,
+}
+
+impl<'a, N, U> GridRenderer<'a, N, U>
+where
+    U: PrimInt + Hash,
+{
+    pub fn new(grid: &'a Grid<N, U>, to_char: impl Fn(&N) -> char + 'a) -> Self {
+        Self {
+            grid,
+            to_char: Box::new(to_char),
+            overlays: Vec::new(),
+            coloured: false,
+            viewport: None,
+        }
+    }
+
+    pub fn cells(
+        mut self,
+        coords: impl IntoIterator<Item = Coord<U>>,
+        mark: impl Into<Mark>,
+    ) -> Self {
+        let mark = mark.into();
+        self.overlays
+            .push(coords.into_iter().map(|coord| (coord, mark)).collect());
+        self
+    }
+
+    pub fn label(mut self, coord: Coord<U>, mark: impl Into<Mark>) -> Self {
+        self.overlays.push(HashMap::from([(coord, mark.into())]));
+        self
+    }
+
+    /// Draws an arrow on every node of the path pointing to the next one, the
+    /// last node (and any jump between non adjacent nodes) being a `*`.
+    pub fn path<C>(mut self, path: &Path<Coord<U>, C>, colour: Option<Colour>) -> Self {
+        let mut overlay = HashMap::new();
+        for (i, node) in path.nodes.iter().enumerate() {
+            let char = path
+                .nodes
+                .get(i + 1)
+                .and_then(|next| {
+                    Direction::all()
+                        .into_iter()
+                        .find(|&dir| node.try_at(dir).as_ref() == Some(next))
+                })
+                .map_or('*', |dir| match dir {
+                    Direction::Up => '^',
+                    Direction::Down => 'v',
+                    Direction::Left => '<',
+                    Direction::Right => '>',
+                });
+            overlay.insert(*node, Mark { char, colour });
+        }
+        self.overlays.push(overlay);
+        self
+    }
+
+    /// Enables ANSI colour escape codes.
+    pub fn coloured(mut self, coloured: bool) -> Self {
+        self.coloured = coloured;
+        self
+    }
+
+    /// Only draws the cells inside `viewport`.
+    pub fn crop(mut self, viewport: Rect<U>) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    fn mark_at(&self, coord: &Coord<U>) -> Option<Mark> {
+        if let Some(mark) = self
+            .overlays
+            .iter()
+            .rev()
+            .find_map(|overlay| overlay.get(coord))
+        {
+            return Some(*mark);
+        }
+
+        self.grid.get(coord).map(|node| (self.to_char)(node).into())
+    }
+
+    fn bounds(&self) -> Option<Rect<U>> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if width.is_zero() || height.is_zero() {
+            return None;
+        }
+
+        let grid_bounds = Rect::new(
+            Coord {
+                x: U::zero(),
+                y: U::zero(),
+            },
+            Coord {
+                x: width - U::one(),
+                y: height - U::one(),
+            },
+        );
+        match self.viewport {
+            None => Some(grid_bounds),
+            Some(viewport) => viewport.intersection(&grid_bounds),
+        }
+    }
+}
+
+impl<N, U> Display for GridRenderer<'_, N, U>
+where
+    U: PrimInt + Hash,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some(Rect { min, max }) = self.bounds() else {
+            return Ok(());
+        };
+
+        let mut y = min.y;
+        while y <= max.y {
+            let mut x = min.x;
+            while x <= max.x {
+                let mark = self.mark_at(&Coord { x, y }).unwrap();
+                match mark.colour {
+                    Some(colour) if self.coloured => {
+                        write!(f, "\x1b[{}m{}\x1b[0m", colour.ansi_code(), mark.char)?
+                    }
+                    _ => f.write_char(mark.char)?,
+                }
+                x = x + U::one();
+            }
+            writeln!(f)?;
+            y = y + U::one();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::SparseGrid;
+
+    fn grid() -> Grid<char> {
+        "....\n.##.\n....\n".parse().unwrap()
+    }
+
+    #[test]
+    fn renders_through_the_mapping() {
+        let grid = grid();
+        let renderer = GridRenderer::new(&grid, |&c| if c == '#' { '█' } else { ' ' });
+        assert_eq!(renderer.to_string(), "    \n ██ \n    \n");
+    }
+
+    #[test]
+    fn overlays_are_stacked() {
+        let grid = grid();
+        let renderer = GridRenderer::new(&grid, |&c| c)
+            .cells([Coord { x: 0, y: 0 }, Coord { x: 1, y: 0 }], 'o')
+            .label(Coord { x: 1, y: 0 }, 'S');
+        assert_eq!(renderer.to_string(), "oS..\n.##.\n....\n");
+    }
+
+    #[test]
+    fn path_is_drawn_with_arrows() {
+        let grid = grid();
+        let path = Path {
+            nodes: vec![
+                Coord { x: 0, y: 0 },
+                Coord { x: 0, y: 1 },
+                Coord { x: 0, y: 2 },
+                Coord { x: 1, y: 2 },
+            ],
+            cost: 3,
+        };
+        let renderer = GridRenderer::new(&grid, |&c| c).path(&path, None);
+        assert_eq!(renderer.to_string(), "v...\nv##.\n>*..\n");
+    }
+
+    #[test]
+    fn colours_only_when_enabled() {
+        let grid: Grid<char> = "..\n".parse().unwrap();
+        let renderer = GridRenderer::new(&grid, |&c| c)
+            .label(Coord { x: 1, y: 0 }, Mark::from('E').coloured(Colour::Red));
+        assert_eq!(renderer.to_string(), ".E\n");
+
+        let renderer = renderer.coloured(true);
+        assert_eq!(renderer.to_string(), ".\x1b[31mE\x1b[0m\n");
+    }
+
+    #[test]
+    fn empty_grid_renders_nothing() {
+        let grid = SparseGrid::new('.').to_grid();
+        assert_eq!(
+            GridRenderer::<char, i64>::new(&grid, |&c| c).to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn crop_to_viewport() {
+        let grid = grid();
+        let renderer = GridRenderer::new(&grid, |&c| c)
+            .crop(Rect::new(Coord { x: 1, y: 1 }, Coord { x: 10, y: 1 }));
+        assert_eq!(renderer.to_string(), "##.\n");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::graph::{astar, Coord, Cost, Direction, Grid, Path, Step};
+use num_traits::{NumCast, PrimInt};
+use std::hash::Hash;
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt + Hash,
+{
+    /// Cheapest path from `from` to `to`, `move_cost(from_cell, to_cell, dir)`
+    /// being the cost of a move, `None` when it is not allowed.
+    ///
+    /// The manhattan distance is used as heuristic, so every move must cost at
+    /// least 1.
+    pub fn shortest_path<C>(
+        &self,
+        from: Coord<U>,
+        to: Coord<U>,
+        move_cost: impl Fn(&N, &N, Direction) -> Option<C>,
+    ) -> Option<Path<Coord<U>, C>>
+    where
+        C: Cost + NumCast,
+    {
+        astar(
+            from,
+            |coord| self.moves(coord, &move_cost),
+            |coord| coord == &to,
+            |coord| C::from(self.manhattan_dist(coord, &to)).unwrap(),
+        )
+    }
+
+    /// Cheapest path from `from` to the nearest cell satisfying `is_goal`, see
+    /// [`Grid::shortest_path`]. Without a known target, there is no heuristic.
+    pub fn shortest_path_to_any<C>(
+        &self,
+        from: Coord<U>,
+        is_goal: impl Fn(&Coord<U>, &N) -> bool,
+        move_cost: impl Fn(&N, &N, Direction) -> Option<C>,
+    ) -> Option<Path<Coord<U>, C>>
+    where
+        C: Cost,
+    {
+        astar(
+            from,
+            |coord| self.moves(coord, &move_cost),
+            |coord| is_goal(coord, self.get(coord).unwrap()),
+            |_| C::default(),
+        )
+    }
+
+    fn moves<'a, C>(
+        &'a self,
+        from: &Coord<U>,
+        move_cost: &'a impl Fn(&N, &N, Direction) -> Option<C>,
+    ) -> impl Iterator<Item = Step<Coord<U>, C>> + 'a {
+        let from = *from;
+        let node = self.get(&from).unwrap();
+        Direction::all().into_iter().filter_map(move |dir| {
+            let to = self.try_at(&from, dir)?;
+            Some(Step {
+                to,
+                additional_cost: move_cost(node, self.get(&to).unwrap(), dir)?,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn maze() -> Grid<char> {
+        "S..#\n.#.#\n...E\n".parse().unwrap()
+    }
+
+    fn walk(_: &char, to: &char, _: Direction) -> Option<u32> {
+        (*to != '#').then_some(1)
+    }
+
+    #[test]
+    fn shortest_path_in_a_maze() {
+        let path = maze()
+            .shortest_path(Coord { x: 0, y: 0 }, Coord { x: 3, y: 2 }, walk)
+            .unwrap();
+
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes.first(), Some(&Coord { x: 0, y: 0 }));
+        assert_eq!(path.nodes.last(), Some(&Coord { x: 3, y: 2 }));
+    }
+
+    #[test]
+    fn move_cost_sees_the_direction() {
+        let grid: Grid<char> = "...\n...\n".parse().unwrap();
+        let path = grid
+            .shortest_path(Coord { x: 0, y: 0 }, Coord { x: 2, y: 0 }, |_, _, dir| {
+                Some(if dir == Direction::Right { 5 } else { 1 })
+            })
+            .unwrap();
+
+        assert_eq!(path.cost, 10);
+    }
+
+    #[test]
+    fn shortest_path_to_the_nearest_goal() {
+        let path = maze()
+            .shortest_path_to_any(Coord { x: 3, y: 2 }, |_, &c| c == 'S', walk)
+            .unwrap();
+
+        assert_eq!(path.cost, 5);
+        assert!(maze()
+            .shortest_path_to_any(Coord { x: 0, y: 0 }, |_, &c| c == 'X', walk)
+            .is_none());
+    }
+}
@@ -0,0 +1,405 @@
+use crate::geom::Rect;
+use crate::graph::{Coord, Grid, Neighbourhood, Topology};
+use num_traits::PrimInt;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Unbounded grid: only cells that have been written are stored, every other
+/// cell holds `default`.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<N, U = i64> {
+    default: N,
+    content: HashMap<Coord<U>, N>,
+    bounds: Option<Rect<U>>,
+}
+
+impl<N, U> SparseGrid<N, U>
+where
+    U: PrimInt + std::hash::Hash,
+{
+    pub fn new(default: N) -> Self {
+        Self {
+            default,
+            content: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn default_value(&self) -> &N {
+        &self.default
+    }
+
+    pub fn get(&self, coord: &Coord<U>) -> &N {
+        self.content.get(coord).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, coord: &Coord<U>) -> &mut N
+    where
+        N: Clone,
+    {
+        self.extend_bounds(coord);
+        self.content
+            .entry(*coord)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn contains(&self, coord: &Coord<U>) -> bool {
+        self.content.contains_key(coord)
+    }
+
+    pub fn insert(&mut self, coord: Coord<U>, node: N) -> Option<N> {
+        self.extend_bounds(&coord);
+        self.content.insert(coord, node)
+    }
+
+    /// Keeps the bounds, see [`shrink_bounds`](Self::shrink_bounds).
+    pub fn remove(&mut self, coord: &Coord<U>) -> Option<N> {
+        self.content.remove(coord)
+    }
+
+    /// Shrinks the bounds to the stored cells, e.g. after removing some.
+    pub fn shrink_bounds(&mut self) {
+        self.bounds = Rect::from_coords(self.content.keys().copied());
+    }
+
+    /// Removes every stored cell, keeping the allocated storage.
+    pub fn clear(&mut self) {
+        self.content.clear();
+        self.bounds = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Stored cells, in no particular order.
+    pub fn entries(&self) -> impl Iterator<Item = (Coord<U>, &N)> + '_ {
+        self.content.iter().map(|(coord, node)| (*coord, node))
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord<U>> + '_ {
+        self.content.keys().copied()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> + '_ {
+        self.content.values()
+    }
+
+    pub fn find(&self, predicate: impl Fn(&N) -> bool) -> Option<Coord<U>> {
+        self.entries()
+            .find(|(_, node)| predicate(node))
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn neighbours<'a>(
+        &'a self,
+        coord: &Coord<U>,
+        neighbourhood: &'a Neighbourhood,
+    ) -> impl Iterator<Item = (Coord<U>, &'a N)> + 'a {
+        coord
+            .neighbours(neighbourhood)
+            .map(|coord| (coord, self.get(&coord)))
+    }
+
+    pub fn map<NewN>(&self, mut f: impl FnMut(&N) -> NewN) -> SparseGrid<NewN, U> {
+        SparseGrid {
+            default: f(&self.default),
+            content: self
+                .content
+                .iter()
+                .map(|(coord, node)| (*coord, f(node)))
+                .collect(),
+            bounds: self.bounds,
+        }
+    }
+
+    /// Rectangle enclosing every stored cell, and every cell of the grid given
+    /// to [`from_grid`](Self::from_grid). It only grows, until
+    /// [`shrink_bounds`](Self::shrink_bounds).
+    pub fn bounds(&self) -> Option<Rect<U>> {
+        self.bounds
+    }
+
+    /// Dense copy of the bounding box: cell `(0, 0)` of the result is the `min`
+    /// corner of [`bounds`](Self::bounds).
+    pub fn to_grid(&self) -> Grid<N, U>
+    where
+        N: Clone,
+    {
+        let Some(bounds) = self.bounds else {
+            return Grid {
+                width: U::zero(),
+                content: Vec::new(),
+                topology: Topology::Bounded,
+            };
+        };
+
+        let (min, width, height) = (bounds.min, bounds.width(), bounds.height());
+        let mut content = Vec::with_capacity((width * height).to_usize().unwrap());
+        for y in 0..height.to_usize().unwrap() {
+            for x in 0..width.to_usize().unwrap() {
+                let coord = Coord {
+                    x: min.x + U::from(x).unwrap(),
+                    y: min.y + U::from(y).unwrap(),
+                };
+                content.push(self.get(&coord).clone());
+            }
+        }
+
+        Grid {
+            width,
+            content,
+            topology: Topology::Bounded,
+        }
+    }
+
+    /// Every cell of `grid` that differs from `default` is stored, cell
+    /// `(0, 0)` of `grid` going to `origin`. The bounds still cover the whole
+    /// grid so that [`to_grid`](Self::to_grid) gives it back.
+    pub fn from_grid(grid: Grid<N, U>, origin: Coord<U>, default: N) -> Self
+    where
+        N: PartialEq,
+    {
+        let bounds = if grid.content.is_empty() {
+            None
+        } else {
+            Some(Rect::new(
+                origin,
+                Coord {
+                    x: origin.x + grid.width() - U::one(),
+                    y: origin.y + grid.height() - U::one(),
+                },
+            ))
+        };
+
+        let width = grid.width.to_usize().unwrap();
+        let content = grid
+            .content
+            .into_iter()
+            .enumerate()
+            .filter(|(_, node)| node != &default)
+            .map(|(i, node)| {
+                let coord = Coord {
+                    x: origin.x + U::from(i % width).unwrap(),
+                    y: origin.y + U::from(i / width).unwrap(),
+                };
+                (coord, node)
+            })
+            .collect();
+
+        Self {
+            default,
+            content,
+            bounds,
+        }
+    }
+
+    fn extend_bounds(&mut self, coord: &Coord<U>) {
+        self.bounds = Some(match self.bounds {
+            None => Rect::point(*coord),
+            Some(bounds) => bounds.extended_to(coord),
+        });
+    }
+}
+
+impl<N, U> Default for SparseGrid<N, U>
+where
+    N: Default,
+    U: PrimInt + std::hash::Hash,
+{
+    fn default() -> Self {
+        Self::new(N::default())
+    }
+}
+
+/// Grids are equal when they hold the same value in every cell and cover the
+/// same bounds: storing a default value explicitly does not matter, extending
+/// the bounds does.
+impl<N, U> PartialEq for SparseGrid<N, U>
+where
+    N: PartialEq,
+    U: PrimInt + std::hash::Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.default == other.default
+            && self.bounds == other.bounds
+            && self
+                .content
+                .keys()
+                .chain(other.content.keys())
+                .all(|coord| self.get(coord) == other.get(coord))
+    }
+}
+
+impl<N, U> Eq for SparseGrid<N, U>
+where
+    N: Eq,
+    U: PrimInt + std::hash::Hash,
+{
+}
+
+impl<N, U> From<Grid<N, U>> for SparseGrid<N, U>
+where
+    N: PartialEq + Default,
+    U: PrimInt + std::hash::Hash,
+{
+    fn from(grid: Grid<N, U>) -> Self {
+        Self::from_grid(
+            grid,
+            Coord {
+                x: U::zero(),
+                y: U::zero(),
+            },
+            N::default(),
+        )
+    }
+}
+
+impl<N, U> From<&SparseGrid<N, U>> for Grid<N, U>
+where
+    N: Clone,
+    U: PrimInt + std::hash::Hash,
+{
+    fn from(sparse: &SparseGrid<N, U>) -> Self {
+        sparse.to_grid()
+    }
+}
+
+impl<N, U> Display for SparseGrid<N, U>
+where
+    N: Display,
+    U: PrimInt + std::hash::Hash,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some(Rect { min, max }) = self.bounds else {
+            return Ok(());
+        };
+
+        let mut y = min.y;
+        while y <= max.y {
+            let mut x = min.x;
+            while x <= max.x {
+                write!(f, "{}", self.get(&Coord { x, y }))?;
+                x = x + U::one();
+            }
+            writeln!(f)?;
+            y = y + U::one();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unset_cells_hold_default() {
+        let grid = SparseGrid::<char>::new('.');
+        assert_eq!(grid.get(&Coord { x: -100, y: 42 }), &'.');
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn bounds_follow_inserts_and_removes() {
+        let mut grid = SparseGrid::<char>::new('.');
+        grid.insert(Coord { x: -2, y: 3 }, '#');
+        grid.insert(Coord { x: 4, y: -1 }, '#');
+        *grid.get_mut(&Coord { x: 0, y: 0 }) = 'o';
+
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Coord { x: -2, y: -1 }, Coord { x: 4, y: 3 }))
+        );
+
+        grid.remove(&Coord { x: 4, y: -1 });
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Coord { x: -2, y: -1 }, Coord { x: 4, y: 3 }))
+        );
+
+        grid.shrink_bounds();
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Coord { x: -2, y: 0 }, Coord { x: 0, y: 3 }))
+        );
+    }
+
+    #[test]
+    fn display_covers_bounds() {
+        let mut grid = SparseGrid::<char>::new('.');
+        grid.insert(Coord { x: -1, y: -1 }, '#');
+        grid.insert(Coord { x: 1, y: 0 }, '#');
+
+        assert_eq!(grid.to_string(), "#..\n..#\n");
+    }
+
+    #[test]
+    fn neighbours_are_unbounded() {
+        let mut grid = SparseGrid::<char>::new('.');
+        grid.insert(Coord { x: 0, y: -1 }, '#');
+
+        let neighbours: HashMap<_, _> = grid
+            .neighbours(&Coord { x: 0, y: 0 }, &Neighbourhood::VonNeumann)
+            .collect();
+        assert_eq!(neighbours.len(), 4);
+        assert_eq!(neighbours[&Coord { x: 0, y: -1 }], &'#');
+        assert_eq!(neighbours[&Coord { x: -1, y: 0 }], &'.');
+    }
+
+    #[test]
+    fn round_trip_with_dense_grid() {
+        let dense: Grid<char, i64> = "...\n.#.\n...\n..#\n".parse().unwrap();
+        let sparse = SparseGrid::from_grid(dense.clone(), Coord { x: 0, y: 0 }, '.');
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.to_grid(), dense);
+    }
+
+    #[test]
+    fn removing_keeps_the_bounds_of_the_dense_grid() {
+        let dense: Grid<char, i64> = "#..\n.#.\n..#\n".parse().unwrap();
+        let mut sparse = SparseGrid::from_grid(dense, Coord { x: 0, y: 0 }, '.');
+
+        sparse.remove(&Coord { x: 0, y: 0 });
+        assert_eq!(sparse.to_grid().to_string(), "...\n.#.\n..#\n");
+    }
+
+    #[test]
+    fn empty_to_grid() {
+        let grid = SparseGrid::<char>::new('.').to_grid();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn round_trip_keeps_negative_bounds() {
+        let mut sparse = SparseGrid::<char>::new('.');
+        sparse.insert(Coord { x: -2, y: -1 }, '#');
+        sparse.insert(Coord { x: 1, y: 0 }, '#');
+        let origin = sparse.bounds().unwrap().min;
+
+        let back = SparseGrid::from_grid(sparse.to_grid(), origin, '.');
+        assert_eq!(back, sparse);
+        assert_eq!(back.get(&Coord { x: -2, y: -1 }), &'#');
+    }
+
+    #[test]
+    fn equality_compares_bounds() {
+        let mut small = SparseGrid::<char>::new('.');
+        small.insert(Coord { x: 0, y: 0 }, '#');
+        let mut large = small.clone();
+        large.insert(Coord { x: 3, y: 3 }, '.');
+
+        assert_ne!(small, large);
+        large.remove(&Coord { x: 3, y: 3 });
+        assert_ne!(small, large);
+        large.shrink_bounds();
+        assert_eq!(small, large);
+    }
+}
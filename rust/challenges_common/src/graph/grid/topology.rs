@@ -0,0 +1,254 @@
+use crate::geom::Rect;
+use crate::graph::{Coord, Direction, Grid};
+use num_traits::PrimInt;
+
+/// How a [`Grid`] behaves at its edges.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Topology<U = usize> {
+    /// Moving past an edge leaves the grid.
+    #[default]
+    Bounded,
+    /// Moving past an edge comes back from the opposite one.
+    Toroidal,
+    /// Cells inside the rectangle wrap within it, the border around it (walls,
+    /// entrances…) stays bounded. Topologies only see coordinates, so a cell
+    /// inside never steps out onto an entrance: moves that must leave the
+    /// rectangle (e.g. out of a blizzard valley) need a [`Topology::Bounded`]
+    /// grid.
+    ToroidalInside(Rect<U>),
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    pub fn topology(&self) -> &Topology<U> {
+        &self.topology
+    }
+
+    pub fn with_topology(mut self, topology: Topology<U>) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn try_at(&self, coord: &Coord<U>, dir: Direction) -> Option<Coord<U>> {
+        self.try_at_dist(coord, dir, U::one())
+    }
+
+    /// Coordinate `dist` cells away from `coord`, wrapped according to the
+    /// topology, or `None` when it falls outside of the grid.
+    pub fn try_at_dist(
+        &self,
+        coord: &Coord<U>,
+        dir: Direction,
+        dist: impl Into<U>,
+    ) -> Option<Coord<U>> {
+        let dist = dist.into();
+        let result = match self.wrapping_area(coord) {
+            None => coord.try_at_dist(dir, dist)?,
+            Some(Rect { min, max }) => {
+                let forward = matches!(dir, Direction::Down | Direction::Right);
+                if dir.is_vertical() {
+                    Coord {
+                        x: coord.x,
+                        y: wrap(coord.y, min.y, max.y, dist, forward),
+                    }
+                } else {
+                    Coord {
+                        x: wrap(coord.x, min.x, max.x, dist, forward),
+                        y: coord.y,
+                    }
+                }
+            }
+        };
+
+        self.is_coord_inside(&result).then_some(result)
+    }
+
+    /// Manhattan distance taking shortcuts through wrapping edges.
+    pub fn manhattan_dist(&self, from: &Coord<U>, to: &Coord<U>) -> U {
+        match (self.wrapping_area(from), self.wrapping_area(to)) {
+            (Some(area), Some(other_area)) if area == other_area => {
+                let dist = |a: U, b: U, size: U| {
+                    let d = if a > b { a - b } else { b - a };
+                    d.min(size - d)
+                };
+                dist(from.x, to.x, area.width()) + dist(from.y, to.y, area.height())
+            }
+            _ => from.manhattan_dist_to(to),
+        }
+    }
+
+    /// Rectangle in which `coord` wraps, if any.
+    fn wrapping_area(&self, coord: &Coord<U>) -> Option<Rect<U>> {
+        match self.topology {
+            Topology::Bounded => None,
+            Topology::Toroidal => self.is_coord_inside(coord).then(|| {
+                Rect::new(
+                    Coord {
+                        x: U::zero(),
+                        y: U::zero(),
+                    },
+                    Coord {
+                        x: self.width() - U::one(),
+                        y: self.height() - U::one(),
+                    },
+                )
+            }),
+            Topology::ToroidalInside(area) => area.contains(coord).then_some(area),
+        }
+    }
+}
+
+fn wrap<U: PrimInt>(value: U, min: U, max: U, dist: U, forward: bool) -> U {
+    let size = max - min + U::one();
+    let offset = value - min;
+    let dist = dist % size;
+    let offset = if forward {
+        (offset + dist) % size
+    } else {
+        (offset + size - dist) % size
+    };
+    min + offset
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Neighbourhood;
+
+    fn grid() -> Grid<char> {
+        "#####\n#...#\n#...#\n#####\n".parse().unwrap()
+    }
+
+    #[test]
+    fn bounded_neighbours_stop_at_edges() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours(&Coord { x: 0, y: 0 }, &Neighbourhood::VonNeumann)
+                .count(),
+            2
+        );
+        assert_eq!(grid.try_at(&Coord { x: 0, y: 0 }, Direction::Up), None);
+    }
+
+    #[test]
+    fn toroidal_neighbours_wrap() {
+        let grid = grid().with_topology(Topology::Toroidal);
+        let neighbours = grid
+            .neighbours(&Coord { x: 0, y: 0 }, &Neighbourhood::VonNeumann)
+            .map(|(coord, _)| coord)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            neighbours,
+            vec![
+                Coord { x: 0, y: 3 },
+                Coord { x: 0, y: 1 },
+                Coord { x: 4, y: 0 },
+                Coord { x: 1, y: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn toroidal_try_at_dist_wraps_several_times() {
+        let grid = grid().with_topology(Topology::Toroidal);
+        assert_eq!(
+            grid.try_at_dist(&Coord { x: 1, y: 1 }, Direction::Left, 12usize),
+            Some(Coord { x: 4, y: 1 })
+        );
+        assert_eq!(
+            grid.try_at_dist(&Coord { x: 1, y: 1 }, Direction::Down, 6usize),
+            Some(Coord { x: 1, y: 3 })
+        );
+    }
+
+    #[test]
+    fn toroidal_inside_wraps_within_walls() {
+        let grid = grid().with_topology(Topology::ToroidalInside(Rect::new(
+            Coord { x: 1, y: 1 },
+            Coord { x: 3, y: 2 },
+        )));
+
+        assert_eq!(
+            grid.try_at(&Coord { x: 3, y: 1 }, Direction::Right),
+            Some(Coord { x: 1, y: 1 })
+        );
+        assert_eq!(
+            grid.try_at(&Coord { x: 1, y: 1 }, Direction::Up),
+            Some(Coord { x: 1, y: 2 })
+        );
+        assert_eq!(grid.try_at(&Coord { x: 0, y: 1 }, Direction::Left), None);
+    }
+
+    #[test]
+    fn toroidal_inside_never_steps_out_onto_entrances() {
+        let grid: Grid<char> = "#.###\n#...#\n#...#\n###.#\n".parse().unwrap();
+        let grid = grid.with_topology(Topology::ToroidalInside(Rect::new(
+            Coord { x: 1, y: 1 },
+            Coord { x: 3, y: 2 },
+        )));
+
+        assert_eq!(
+            grid.try_at(&Coord { x: 1, y: 0 }, Direction::Down),
+            Some(Coord { x: 1, y: 1 })
+        );
+        assert_eq!(
+            grid.try_at(&Coord { x: 1, y: 1 }, Direction::Up),
+            Some(Coord { x: 1, y: 2 })
+        );
+        assert_eq!(
+            grid.try_at(&Coord { x: 3, y: 2 }, Direction::Down),
+            Some(Coord { x: 3, y: 1 })
+        );
+    }
+
+    #[test]
+    fn toroidal_manhattan_dist_takes_shortcuts() {
+        let grid = grid().with_topology(Topology::Toroidal);
+        assert_eq!(
+            grid.manhattan_dist(&Coord { x: 0, y: 0 }, &Coord { x: 4, y: 3 }),
+            2
+        );
+
+        let grid = grid.with_topology(Topology::Bounded);
+        assert_eq!(
+            grid.manhattan_dist(&Coord { x: 0, y: 0 }, &Coord { x: 4, y: 3 }),
+            7
+        );
+    }
+
+    #[test]
+    fn toroidal_neighbours_on_narrow_axes_are_distinct() {
+        let coords = |grid: &Grid<char>, coord| {
+            grid.neighbours(&coord, &Neighbourhood::VonNeumann)
+                .map(|(coord, _)| coord)
+                .collect::<Vec<_>>()
+        };
+
+        let row: Grid<char> = "...\n".parse().unwrap();
+        let row = row.with_topology(Topology::Toroidal);
+        assert_eq!(
+            coords(&row, Coord { x: 1, y: 0 }),
+            vec![Coord { x: 0, y: 0 }, Coord { x: 2, y: 0 }]
+        );
+
+        let columns: Grid<char> = "..\n..\n..\n".parse().unwrap();
+        let columns = columns.with_topology(Topology::Toroidal);
+        assert_eq!(
+            coords(&columns, Coord { x: 0, y: 1 }),
+            vec![
+                Coord { x: 0, y: 0 },
+                Coord { x: 0, y: 2 },
+                Coord { x: 1, y: 1 }
+            ]
+        );
+        assert_eq!(
+            columns
+                .neighbours(&Coord { x: 0, y: 1 }, &Neighbourhood::Moore)
+                .count(),
+            5
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use crate::geom::Rect;
+use crate::graph::{Coord, Grid, Topology};
+use num_traits::PrimInt;
+use std::fmt::{Display, Formatter};
+
+/// The 8 ways to lay a rectangle back onto itself (rotations and mirrors).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Symmetry {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub fn all() -> [Symmetry; 8] {
+        use Symmetry::*;
+        [
+            Identity,
+            RotateCw,
+            Rotate180,
+            RotateCcw,
+            FlipHorizontal,
+            FlipVertical,
+            Transpose,
+            AntiTranspose,
+        ]
+    }
+
+    /// Whether width and height are exchanged.
+    pub fn swaps_axes(&self) -> bool {
+        self.parts().0
+    }
+
+    /// Symmetry equivalent to applying `self` then `next`.
+    pub fn then(&self, next: Symmetry) -> Symmetry {
+        let (t1, fx1, fy1) = self.parts();
+        let (t2, fx2, fy2) = next.parts();
+        let (fx1, fy1) = if t2 { (fy1, fx1) } else { (fx1, fy1) };
+        Self::from_parts(t1 ^ t2, fx1 ^ fx2, fy1 ^ fy2)
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        let (t, fx, fy) = self.parts();
+        if t {
+            Self::from_parts(t, fy, fx)
+        } else {
+            *self
+        }
+    }
+
+    /// Where `coord` of a `width`×`height` rectangle lands once transformed.
+    pub fn apply<U: PrimInt>(&self, coord: &Coord<U>, width: U, height: U) -> Coord<U> {
+        let (t, fx, fy) = self.parts();
+        let (Coord { mut x, mut y }, width, height) = if t {
+            (
+                Coord {
+                    x: coord.y,
+                    y: coord.x,
+                },
+                height,
+                width,
+            )
+        } else {
+            (*coord, width, height)
+        };
+
+        if fx {
+            x = width - U::one() - x;
+        }
+        if fy {
+            y = height - U::one() - y;
+        }
+
+        Coord { x, y }
+    }
+
+    /// `(transpose, flip x, flip y)`, applied in that order.
+    fn parts(&self) -> (bool, bool, bool) {
+        use Symmetry::*;
+        match self {
+            Identity => (false, false, false),
+            FlipHorizontal => (false, true, false),
+            FlipVertical => (false, false, true),
+            Rotate180 => (false, true, true),
+            Transpose => (true, false, false),
+            RotateCw => (true, true, false),
+            RotateCcw => (true, false, true),
+            AntiTranspose => (true, true, true),
+        }
+    }
+
+    fn from_parts(transpose: bool, flip_x: bool, flip_y: bool) -> Self {
+        use Symmetry::*;
+        match (transpose, flip_x, flip_y) {
+            (false, false, false) => Identity,
+            (false, true, false) => FlipHorizontal,
+            (false, false, true) => FlipVertical,
+            (false, true, true) => Rotate180,
+            (true, false, false) => Transpose,
+            (true, true, false) => RotateCw,
+            (true, false, true) => RotateCcw,
+            (true, true, true) => AntiTranspose,
+        }
+    }
+}
+
+/// Read-only transformed view of a [`Grid`]: the grid is tiled `tiles` times
+/// and then transformed by `symmetry`, nothing is copied.
+#[derive(Clone, Copy, Debug)]
+pub struct GridView<'a, N, U = usize> {
+    grid: &'a Grid<N, U>,
+    symmetry: Symmetry,
+    tiles: (usize, usize),
+}
+
+impl<'a, N, U> GridView<'a, N, U>
+where
+    U: PrimInt,
+{
+    pub fn transform(self, symmetry: Symmetry) -> Self {
+        Self {
+            symmetry: self.symmetry.then(symmetry),
+            ..self
+        }
+    }
+
+    pub fn rotate_cw(self) -> Self {
+        self.transform(Symmetry::RotateCw)
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        self.transform(Symmetry::RotateCcw)
+    }
+
+    pub fn transpose(self) -> Self {
+        self.transform(Symmetry::Transpose)
+    }
+
+    pub fn flip_horizontal(self) -> Self {
+        self.transform(Symmetry::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(self) -> Self {
+        self.transform(Symmetry::FlipVertical)
+    }
+
+    /// Repeats the view `nx` times horizontally and `ny` times vertically.
+    pub fn tile(self, nx: usize, ny: usize) -> Self {
+        let (nx, ny) = if self.symmetry.swaps_axes() {
+            (ny, nx)
+        } else {
+            (nx, ny)
+        };
+        Self {
+            tiles: (self.tiles.0 * nx, self.tiles.1 * ny),
+            ..self
+        }
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    pub fn width(&self) -> U {
+        self.dimensions().0
+    }
+
+    pub fn height(&self) -> U {
+        self.dimensions().1
+    }
+
+    pub fn get(&self, coord: &Coord<U>) -> Option<&'a N> {
+        let (width, height) = self.dimensions();
+        // an empty grid gives an empty view: nothing to wrap into below
+        if coord.x < U::zero() || coord.x >= width || coord.y < U::zero() || coord.y >= height {
+            return None;
+        }
+
+        let tiled = self.symmetry.inverse().apply(coord, width, height);
+        self.grid.get(&Coord {
+            x: tiled.x % self.grid.width(),
+            y: tiled.y % self.grid.height(),
+        })
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord<U>> {
+        let (width, height) = self.dimensions();
+        (0..height.to_usize().unwrap()).flat_map(move |y| {
+            (0..width.to_usize().unwrap()).map(move |x| Coord {
+                x: U::from(x).unwrap(),
+                y: U::from(y).unwrap(),
+            })
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (Coord<U>, &'a N)> + '_ {
+        self.coords()
+            .map(|coord| (coord, self.get(&coord).unwrap()))
+    }
+
+    pub fn to_grid(&self) -> Grid<N, U>
+    where
+        N: Clone,
+    {
+        let topology = match self.grid.topology {
+            Topology::ToroidalInside(area) if self.tiles == (1, 1) => {
+                let (width, height) = (self.grid.width(), self.grid.height());
+                let corners =
+                    [area.min, area.max].map(|corner| self.symmetry.apply(&corner, width, height));
+                Topology::ToroidalInside(Rect::from_coords(corners).unwrap())
+            }
+            Topology::ToroidalInside(_) => Topology::Bounded,
+            topology => topology,
+        };
+
+        Grid {
+            width: self.width(),
+            content: self.entries().map(|(_, node)| node.clone()).collect(),
+            topology,
+        }
+    }
+
+    fn dimensions(&self) -> (U, U) {
+        let width = self.grid.width() * U::from(self.tiles.0).unwrap();
+        let height = self.grid.height() * U::from(self.tiles.1).unwrap();
+        if self.symmetry.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+}
+
+impl<N, U> Display for GridView<'_, N, U>
+where
+    N: Display,
+    U: PrimInt,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self.width();
+        for (coord, node) in self.entries() {
+            write!(f, "{}", node)?;
+            if coord.x == width - U::one() {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N, U> Grid<N, U>
+where
+    U: PrimInt,
+{
+    pub fn view(&self) -> GridView<'_, N, U> {
+        GridView {
+            grid: self,
+            symmetry: Symmetry::Identity,
+            tiles: (1, 1),
+        }
+    }
+
+    pub fn transform(&self, symmetry: Symmetry) -> Self
+    where
+        N: Clone,
+    {
+        self.view().transform(symmetry).to_grid()
+    }
+
+    pub fn rotate_cw(&self) -> Self
+    where
+        N: Clone,
+    {
+        self.transform(Symmetry::RotateCw)
+    }
+
+    pub fn rotate_ccw(&self) -> Self
+    where
+        N: Clone,
+    {
+        self.transform(Symmetry::RotateCcw)
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        N: Clone,
+    {
+        self.transform(Symmetry::Transpose)
+    }
+
+    pub fn flip_horizontal(&self) -> Self
+    where
+        N: Clone,
+    {
+        self.transform(Symmetry::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(&self) -> Self
+    where
+        N: Clone,
+    {
+        self.transform(Symmetry::FlipVertical)
+    }
+
+    pub fn tile(&self, nx: usize, ny: usize) -> Self
+    where
+        N: Clone,
+    {
+        self.view().tile(nx, ny).to_grid()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    #[test]
+    fn empty_grid_views_are_empty() {
+        let grid = Grid::<char> {
+            width: 0,
+            content: Vec::new(),
+            topology: Topology::Bounded,
+        };
+
+        let view = grid.view().rotate_cw().tile(2, 3);
+        assert_eq!((view.width(), view.height()), (0, 0));
+        assert_eq!(view.get(&Coord { x: 0, y: 0 }), None);
+        assert_eq!(view.to_string(), "");
+        assert_eq!(grid.rotate_cw(), grid);
+    }
+
+    #[test]
+    fn rotate_cw() {
+        assert_eq!(grid().rotate_cw().to_string(), "da\neb\nfc\n");
+    }
+
+    #[test]
+    fn rotate_ccw() {
+        assert_eq!(grid().rotate_ccw().to_string(), "cf\nbe\nad\n");
+    }
+
+    #[test]
+    fn transpose_and_flips() {
+        assert_eq!(grid().transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid().flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid().flip_vertical().to_string(), "def\nabc\n");
+    }
+
+    #[test]
+    fn four_rotations_are_identity() {
+        let grid = grid();
+        let view = grid.view().rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(view.symmetry(), Symmetry::Identity);
+        assert_eq!(view.to_grid(), grid);
+    }
+
+    #[test]
+    fn all_symmetries_are_distinct() {
+        let grid = grid();
+        let transformed: HashSet<_> = Symmetry::all()
+            .into_iter()
+            .map(|symmetry| grid.transform(symmetry).to_string())
+            .collect();
+        assert_eq!(transformed.len(), 8);
+    }
+
+    #[test]
+    fn composed_views_match_sequential_copies() {
+        let grid = grid();
+        for a in Symmetry::all() {
+            for b in Symmetry::all() {
+                assert_eq!(
+                    grid.view().transform(a).transform(b).to_grid(),
+                    grid.transform(a).transform(b)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tile() {
+        assert_eq!(
+            grid().tile(2, 2).to_string(),
+            "abcabc\ndefdef\nabcabc\ndefdef\n"
+        );
+    }
+
+    #[test]
+    fn tile_a_rotated_view() {
+        let grid = grid();
+        let view = grid.view().rotate_cw().tile(2, 1);
+        assert_eq!(view.to_string(), "dada\nebeb\nfcfc\n");
+        assert_eq!(view.get(&Coord { x: 3, y: 2 }), Some(&'c'));
+        assert_eq!(view.get(&Coord { x: 4, y: 0 }), None);
+    }
+}
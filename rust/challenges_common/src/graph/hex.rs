@@ -0,0 +1,663 @@
+use num_traits::{PrimInt, Signed};
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Axial coordinates of a hexagonal cell; the third cube coordinate is
+/// `s = -q - r`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct HexCoord<U = i32> {
+    pub q: U,
+    pub r: U,
+}
+
+impl<U> HexCoord<U>
+where
+    U: PrimInt + Signed,
+{
+    pub fn new(q: U, r: U) -> Self {
+        Self { q, r }
+    }
+
+    pub fn origin() -> Self {
+        Self::new(U::zero(), U::zero())
+    }
+
+    /// `None` if `q + r + s != 0`.
+    pub fn from_cube(q: U, r: U, s: U) -> Option<Self> {
+        (q + r + s).is_zero().then_some(Self { q, r })
+    }
+
+    pub fn s(&self) -> U {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(&self) -> (U, U, U) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn at(&self, dir: HexDirection) -> Self {
+        *self + dir.into()
+    }
+
+    pub fn at_dist(&self, dir: HexDirection, dist: U) -> Self {
+        *self + HexCoord::from(dir) * dist
+    }
+
+    pub fn neighbours(&self) -> impl Iterator<Item = Self> {
+        let coord = *self;
+        HexDirection::all()
+            .into_iter()
+            .map(move |dir| coord.at(dir))
+    }
+
+    pub fn dist_to(&self, to: &Self) -> U {
+        let diff = *to - *self;
+        (diff.q.abs() + diff.r.abs() + diff.s().abs()) / (U::one() + U::one())
+    }
+
+    /// Cells exactly `radius` steps away from `self`, going around
+    /// counter-clockwise (in screen coordinates) from the one in the [`HexDirection::SouthWest`]
+    /// direction.
+    pub fn ring(&self, radius: U) -> Vec<Self> {
+        if radius.is_zero() {
+            return vec![*self];
+        }
+
+        let mut result = Vec::with_capacity(6 * radius.to_usize().unwrap());
+        let mut current = self.at_dist(HexDirection::SouthWest, radius);
+        for dir in HexDirection::all() {
+            let mut i = U::zero();
+            while i < radius {
+                result.push(current);
+                current = current.at(dir);
+                i = i + U::one();
+            }
+        }
+        result
+    }
+
+    /// Every cell at most `radius` steps away from `self`, ring after ring.
+    pub fn spiral(&self, radius: U) -> Vec<Self> {
+        let mut result = vec![*self];
+        let mut i = U::one();
+        while i <= radius {
+            result.extend(self.ring(i));
+            i = i + U::one();
+        }
+        result
+    }
+
+    /// Rotation by 60° clockwise (in screen coordinates) around `center`.
+    pub fn rotate_cw(&self, center: &Self) -> Self {
+        let relative = *self - *center;
+        *center
+            + HexCoord {
+                q: -relative.r,
+                r: -relative.s(),
+            }
+    }
+
+    pub fn rotate_ccw(&self, center: &Self) -> Self {
+        let relative = *self - *center;
+        *center
+            + HexCoord {
+                q: -relative.s(),
+                r: -relative.q,
+            }
+    }
+}
+
+impl<U: PrimInt> Add for HexCoord<U> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            q: self.q + rhs.q,
+            r: self.r + rhs.r,
+        }
+    }
+}
+
+impl<U: PrimInt> Sub for HexCoord<U> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            q: self.q - rhs.q,
+            r: self.r - rhs.r,
+        }
+    }
+}
+
+impl<U: PrimInt> Mul<U> for HexCoord<U> {
+    type Output = Self;
+
+    fn mul(self, rhs: U) -> Self::Output {
+        Self {
+            q: self.q * rhs,
+            r: self.r * rhs,
+        }
+    }
+}
+
+/// The 6 neighbouring directions, named after the [`Orientation::PointyTop`]
+/// layout, in counter-clockwise order.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl HexDirection {
+    pub fn all() -> [HexDirection; 6] {
+        use HexDirection::*;
+        [East, NorthEast, NorthWest, West, SouthWest, SouthEast]
+    }
+
+    pub fn rotate_cw(&self, steps: usize) -> Self {
+        Self::all()[(self.index() + 6 - steps % 6) % 6]
+    }
+
+    pub fn rotate_ccw(&self, steps: usize) -> Self {
+        Self::all()[(self.index() + steps) % 6]
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_ccw(3)
+    }
+
+    /// Usual compass name of the direction in the given orientation.
+    pub fn compass(&self, orientation: Orientation) -> &'static str {
+        let names = orientation.compass_names();
+        names[self.index()]
+    }
+
+    pub fn from_compass(orientation: Orientation, name: &str) -> Option<Self> {
+        let names = orientation.compass_names();
+        names
+            .iter()
+            .position(|candidate| candidate.eq_ignore_ascii_case(name))
+            .map(|index| Self::all()[index])
+    }
+
+    fn index(&self) -> usize {
+        Self::all().iter().position(|dir| dir == self).unwrap()
+    }
+}
+
+impl<U: PrimInt + Signed> From<HexDirection> for HexCoord<U> {
+    fn from(dir: HexDirection) -> Self {
+        let (one, zero) = (U::one(), U::zero());
+        let (q, r) = match dir {
+            HexDirection::East => (one, zero),
+            HexDirection::NorthEast => (one, -one),
+            HexDirection::NorthWest => (zero, -one),
+            HexDirection::West => (-one, zero),
+            HexDirection::SouthWest => (-one, one),
+            HexDirection::SouthEast => (zero, one),
+        };
+        HexCoord { q, r }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Orientation {
+    /// Hexes have a flat top edge: columns are aligned, every other one is
+    /// shifted half a cell down.
+    FlatTop,
+    /// Hexes have a pointy top: rows are aligned, every other one is shifted
+    /// half a cell right.
+    PointyTop,
+}
+
+impl Orientation {
+    fn compass_names(&self) -> [&'static str; 6] {
+        match self {
+            Orientation::FlatTop => ["se", "ne", "n", "nw", "sw", "s"],
+            Orientation::PointyTop => ["e", "ne", "nw", "w", "sw", "se"],
+        }
+    }
+
+    /// Axial coordinates of a char in a "doubled" text layout: with pointy
+    /// tops, cells of a row are two columns apart; with flat tops, cells of a
+    /// column are two lines apart.
+    fn doubled_to_axial<U: PrimInt + Signed>(&self, column: U, line: U) -> Option<HexCoord<U>> {
+        let two = U::one() + U::one();
+        match self {
+            Orientation::PointyTop => ((column - line) % two).is_zero().then(|| HexCoord {
+                q: (column - line) / two,
+                r: line,
+            }),
+            Orientation::FlatTop => ((line - column) % two).is_zero().then(|| HexCoord {
+                q: column,
+                r: (line - column) / two,
+            }),
+        }
+    }
+}
+
+/// "Offset" text layouts, one char per cell, every other row (`R`) or column
+/// (`Q`) being shoved by half a cell: right for rows, down for columns.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum OffsetLayout {
+    /// Pointy tops, odd rows shoved right.
+    OddR,
+    /// Pointy tops, even rows shoved right.
+    EvenR,
+    /// Flat tops, odd columns shoved down.
+    OddQ,
+    /// Flat tops, even columns shoved down.
+    EvenQ,
+}
+
+impl OffsetLayout {
+    pub fn orientation(&self) -> Orientation {
+        match self {
+            OffsetLayout::OddR | OffsetLayout::EvenR => Orientation::PointyTop,
+            OffsetLayout::OddQ | OffsetLayout::EvenQ => Orientation::FlatTop,
+        }
+    }
+
+    fn offset_to_axial<U: PrimInt + Signed>(&self, column: U, line: U) -> HexCoord<U> {
+        let (one, two) = (U::one(), U::one() + U::one());
+        match self {
+            OffsetLayout::OddR => HexCoord {
+                q: column - (line - (line & one)) / two,
+                r: line,
+            },
+            OffsetLayout::EvenR => HexCoord {
+                q: column - (line + (line & one)) / two,
+                r: line,
+            },
+            OffsetLayout::OddQ => HexCoord {
+                q: column,
+                r: line - (column - (column & one)) / two,
+            },
+            OffsetLayout::EvenQ => HexCoord {
+                q: column,
+                r: line - (column + (column & one)) / two,
+            },
+        }
+    }
+}
+
+/// Dense hexagonal grid of arbitrary shape: cells are stored over their axial
+/// bounding parallelogram.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HexGrid<N, U = i32> {
+    orientation: Orientation,
+    min: HexCoord<U>,
+    width: usize,
+    content: Vec<Option<N>>,
+}
+
+impl<N, U> HexGrid<N, U>
+where
+    U: PrimInt + Signed,
+{
+    pub fn from_cells(
+        orientation: Orientation,
+        cells: impl IntoIterator<Item = (HexCoord<U>, N)>,
+    ) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let (Some(min_q), Some(min_r)) = (
+            cells.iter().map(|(coord, _)| coord.q).min(),
+            cells.iter().map(|(coord, _)| coord.r).min(),
+        ) else {
+            return Self {
+                orientation,
+                min: HexCoord::origin(),
+                width: 0,
+                content: Vec::new(),
+            };
+        };
+        let max_q = cells.iter().map(|(coord, _)| coord.q).max().unwrap();
+        let max_r = cells.iter().map(|(coord, _)| coord.r).max().unwrap();
+
+        let width = (max_q - min_q).to_usize().unwrap() + 1;
+        let height = (max_r - min_r).to_usize().unwrap() + 1;
+        let mut result = Self {
+            orientation,
+            min: HexCoord::new(min_q, min_r),
+            width,
+            content: (0..width * height).map(|_| None).collect(),
+        };
+        for (coord, node) in cells {
+            let index = result.index(&coord).unwrap();
+            result.content[index] = Some(node);
+        }
+        result
+    }
+
+    /// Parses a "doubled" text layout, whitespace being the gaps between cells:
+    ///
+    /// ```text
+    /// pointy top:    flat top:
+    ///  a b c         a c
+    /// d e f g         b d
+    ///  h i j         e g
+    ///                 f
+    /// ```
+    ///
+    /// See [`HexGrid::parse_offset`] for the one char per cell layouts.
+    pub fn parse(s: &str, orientation: Orientation) -> Result<Self, CannotParseHexGrid<N::Error>>
+    where
+        N: TryFrom<char>,
+        N::Error: std::error::Error,
+    {
+        let mut parity = None;
+        Self::parse_with(s, orientation, |column, line| {
+            let parity = *parity.get_or_insert((line + column) % 2);
+            orientation.doubled_to_axial(
+                U::from(column).unwrap() - U::from(parity).unwrap(),
+                U::from(line).unwrap(),
+            )
+        })
+    }
+
+    /// Parses an offset text layout, one char per cell, whitespace being
+    /// missing cells. With [`OffsetLayout::OddR`], `abc` / `def` stands for:
+    ///
+    /// ```text
+    /// a b c
+    ///  d e f
+    /// ```
+    pub fn parse_offset(s: &str, layout: OffsetLayout) -> Result<Self, CannotParseHexGrid<N::Error>>
+    where
+        N: TryFrom<char>,
+        N::Error: std::error::Error,
+    {
+        Self::parse_with(s, layout.orientation(), |column, line| {
+            Some(layout.offset_to_axial(U::from(column).unwrap(), U::from(line).unwrap()))
+        })
+    }
+
+    fn parse_with(
+        s: &str,
+        orientation: Orientation,
+        mut to_axial: impl FnMut(usize, usize) -> Option<HexCoord<U>>,
+    ) -> Result<Self, CannotParseHexGrid<N::Error>>
+    where
+        N: TryFrom<char>,
+        N::Error: std::error::Error,
+    {
+        let mut cells = Vec::new();
+
+        for (line, content) in s.lines().enumerate() {
+            for (column, char) in content.chars().enumerate() {
+                if char.is_whitespace() {
+                    continue;
+                }
+
+                let coord = to_axial(column, line).ok_or(CannotParseHexGrid::MisalignedCell {
+                    line: line + 1,
+                    column: column + 1,
+                })?;
+                let node =
+                    N::try_from(char).map_err(|source| CannotParseHexGrid::CannotParseNode {
+                        line: line + 1,
+                        column: column + 1,
+                        char,
+                        source,
+                    })?;
+                cells.push((coord, node));
+            }
+        }
+
+        Ok(Self::from_cells(orientation, cells))
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn len(&self) -> usize {
+        self.content.iter().filter(|node| node.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.iter().all(|node| node.is_none())
+    }
+
+    pub fn get(&self, coord: &HexCoord<U>) -> Option<&N> {
+        self.content.get(self.index(coord)?)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, coord: &HexCoord<U>) -> Option<&mut N> {
+        let index = self.index(coord)?;
+        self.content.get_mut(index)?.as_mut()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (HexCoord<U>, &N)> + '_ {
+        self.content
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| Some((self.coord(index), node.as_ref()?)))
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = HexCoord<U>> + '_ {
+        self.entries().map(|(coord, _)| coord)
+    }
+
+    pub fn find(&self, predicate: impl Fn(&N) -> bool) -> Option<HexCoord<U>> {
+        self.entries()
+            .find(|(_, node)| predicate(node))
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn neighbours(&self, coord: &HexCoord<U>) -> impl Iterator<Item = (HexCoord<U>, &N)> + '_ {
+        coord
+            .neighbours()
+            .filter_map(|coord| self.get(&coord).map(|node| (coord, node)))
+    }
+
+    pub fn map<NewN>(&self, mut f: impl FnMut(&N) -> NewN) -> HexGrid<NewN, U> {
+        HexGrid {
+            orientation: self.orientation,
+            min: self.min,
+            width: self.width,
+            content: self
+                .content
+                .iter()
+                .map(|node| node.as_ref().map(&mut f))
+                .collect(),
+        }
+    }
+
+    fn index(&self, coord: &HexCoord<U>) -> Option<usize> {
+        let q = (coord.q - self.min.q).to_usize()?;
+        let r = (coord.r - self.min.r).to_usize()?;
+        (q < self.width).then_some(q + r * self.width)
+    }
+
+    fn coord(&self, index: usize) -> HexCoord<U> {
+        HexCoord {
+            q: self.min.q + U::from(index % self.width).unwrap(),
+            r: self.min.r + U::from(index / self.width).unwrap(),
+        }
+    }
+}
+
+impl<N, U> FromStr for HexGrid<N, U>
+where
+    N: TryFrom<char>,
+    N::Error: std::error::Error,
+    U: PrimInt + Signed,
+{
+    type Err = CannotParseHexGrid<N::Error>;
+
+    /// Parses a [`Orientation::PointyTop`] layout.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Orientation::PointyTop)
+    }
+}
+
+/// Lines and columns are 1-based.
+#[derive(Error, Debug)]
+pub enum CannotParseHexGrid<T: std::error::Error> {
+    #[error("Cannot parse hex grid: '{char}' at line {line}, column {column}: {source}")]
+    CannotParseNode {
+        line: usize,
+        column: usize,
+        char: char,
+        #[source]
+        source: T,
+    },
+    #[error("Cannot parse hex grid: cell at line {line}, column {column} is not aligned with the others")]
+    MisalignedCell { line: usize, column: usize },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+    use std::collections::HashSet;
+
+    type Coord = HexCoord<i32>;
+
+    #[test]
+    fn neighbours_are_at_dist_1() {
+        let origin = Coord::origin();
+        assert!(origin.neighbours().all(|n| n.dist_to(&origin) == 1));
+        assert_eq!(origin.neighbours().collect::<HashSet<_>>().len(), 6);
+    }
+
+    #[test]
+    fn dist() {
+        assert_eq!(Coord::new(0, 0).dist_to(&Coord::new(3, -1)), 3);
+        assert_eq!(Coord::new(-2, 1).dist_to(&Coord::new(1, 1)), 3);
+        assert_eq!(Coord::new(0, 0).dist_to(&Coord::new(2, 2)), 4);
+    }
+
+    #[test]
+    fn cube_conversions() {
+        let coord = Coord::new(2, -3);
+        assert_eq!(coord.to_cube(), (2, -3, 1));
+        assert_eq!(Coord::from_cube(2, -3, 1), Some(coord));
+        assert_eq!(Coord::from_cube(2, -3, 0), None);
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        let center = Coord::new(1, 1);
+        let ring = center.ring(2);
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|coord| coord.dist_to(&center) == 2));
+
+        let spiral = center.spiral(2);
+        assert_eq!(spiral.len(), 19);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 19);
+    }
+
+    #[test]
+    fn rotations() {
+        let center = Coord::new(1, 0);
+        let east = center.at(HexDirection::East);
+        assert_eq!(east.rotate_cw(&center), center.at(HexDirection::SouthEast));
+        assert_eq!(east.rotate_ccw(&center), center.at(HexDirection::NorthEast));
+
+        let mut coord = Coord::new(3, -1);
+        for _ in 0..6 {
+            coord = coord.rotate_cw(&center);
+        }
+        assert_eq!(coord, Coord::new(3, -1));
+
+        assert_eq!(HexDirection::East.rotate_cw(1), HexDirection::SouthEast);
+        assert_eq!(HexDirection::East.opposite(), HexDirection::West);
+    }
+
+    #[test]
+    fn compass_names() {
+        assert_eq!(
+            HexDirection::from_compass(Orientation::FlatTop, "N"),
+            Some(HexDirection::NorthWest)
+        );
+        assert_eq!(
+            HexDirection::SouthEast.compass(Orientation::PointyTop),
+            "se"
+        );
+    }
+
+    #[test]
+    fn parse_pointy_top() {
+        let grid: HexGrid<char> = " a b\nc d e\n f g\n".parse().unwrap();
+
+        assert_eq!(grid.len(), 7);
+        let d = grid.find(|&c| c == 'd').unwrap();
+        let mut neighbours: Vec<_> = grid.neighbours(&d).map(|(_, &c)| c).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!['a', 'b', 'c', 'e', 'f', 'g']);
+        assert_eq!(grid.get(&d.at(HexDirection::NorthWest)), Some(&'a'));
+    }
+
+    #[test]
+    fn parse_flat_top() {
+        let grid: HexGrid<char> = HexGrid::parse("a c\n b \nd e\n", Orientation::FlatTop).unwrap();
+
+        let b = grid.find(|&c| c == 'b').unwrap();
+        assert_eq!(grid.neighbours(&b).count(), 4);
+        assert_eq!(
+            grid.get(&b.at(HexDirection::from_compass(Orientation::FlatTop, "sw").unwrap())),
+            Some(&'d')
+        );
+    }
+
+    #[test]
+    fn misaligned_cell() {
+        let result = " a b\nc  d\n".parse::<HexGrid<char>>();
+        assert!(matches!(
+            result,
+            Err(CannotParseHexGrid::MisalignedCell { line: 2, column: 4 })
+        ));
+    }
+
+    #[test]
+    fn parse_doc_examples() {
+        let pointy: HexGrid<char> = " a b c\nd e f g\n h i j\n".parse().unwrap();
+        let e = pointy.find(|&c| c == 'e').unwrap();
+        let mut neighbours: Vec<_> = pointy.neighbours(&e).map(|(_, &c)| c).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!['a', 'b', 'd', 'f', 'h', 'i']);
+
+        let flat: HexGrid<char> =
+            HexGrid::parse("a c\n b d\ne g\n f\n", Orientation::FlatTop).unwrap();
+        assert_eq!(flat.len(), 7);
+        let b = flat.find(|&c| c == 'b').unwrap();
+        let mut neighbours: Vec<_> = flat.neighbours(&b).map(|(_, &c)| c).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!['a', 'c', 'e', 'f', 'g']);
+    }
+
+    #[test]
+    fn parse_offset_layouts() {
+        let neighbours_of_e = |layout| {
+            let grid: HexGrid<char> = HexGrid::parse_offset("abc\ndef\nghi\n", layout).unwrap();
+            let e = grid.find(|&c| c == 'e').unwrap();
+            let mut neighbours: String = grid.neighbours(&e).map(|(_, &c)| c).collect();
+            neighbours = neighbours.chars().sorted().collect();
+            (grid.orientation(), neighbours)
+        };
+
+        assert_eq!(
+            neighbours_of_e(OffsetLayout::OddR),
+            (Orientation::PointyTop, "bcdfhi".to_string())
+        );
+        assert_eq!(
+            neighbours_of_e(OffsetLayout::EvenR),
+            (Orientation::PointyTop, "abdfgh".to_string())
+        );
+        assert_eq!(
+            neighbours_of_e(OffsetLayout::OddQ),
+            (Orientation::FlatTop, "bdfghi".to_string())
+        );
+        assert_eq!(
+            neighbours_of_e(OffsetLayout::EvenQ),
+            (Orientation::FlatTop, "abcdfh".to_string())
+        );
+    }
+}
@@ -1,5 +1,15 @@
 mod astar;
+mod bidirectional;
+mod dijkstra;
 pub mod grid;
+pub mod hex;
+pub mod space3d;
+pub mod topo;
+mod traversal;
 
 pub use astar::*;
+pub use bidirectional::*;
+pub use dijkstra::*;
 pub use grid::*;
+pub use space3d::*;
+pub use traversal::*;
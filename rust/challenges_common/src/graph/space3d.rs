@@ -0,0 +1,380 @@
+use itertools::Itertools;
+use num_traits::{PrimInt, Signed};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Coord3<U = i32> {
+    pub x: U,
+    pub y: U,
+    pub z: U,
+}
+
+impl<U> Coord3<U> {
+    pub fn new(x: U, y: U, z: U) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<U: PrimInt + Signed> Coord3<U> {
+    pub fn origin() -> Self {
+        Self::new(U::zero(), U::zero(), U::zero())
+    }
+
+    pub fn manhattan_dist_to(&self, to: &Self) -> U {
+        (*to - *self).manhattan_len()
+    }
+
+    /// The cells around, selected by `neighbourhood`.
+    pub fn neighbours(&self, neighbourhood: &Neighbourhood3) -> impl Iterator<Item = Self> {
+        let coord = *self;
+        let max_moved_axes = neighbourhood.max_moved_axes();
+        let range = [-U::one(), U::zero(), U::one()];
+        range
+            .into_iter()
+            .cartesian_product(range)
+            .cartesian_product(range)
+            .filter(move |&((x, y), z)| {
+                let moved_axes = [x, y, z].iter().filter(|v| !v.is_zero()).count();
+                (1..=max_moved_axes).contains(&moved_axes)
+            })
+            .map(move |((x, y), z)| &coord + &Vec3::new(x, y, z))
+    }
+}
+
+/// Which cells of the surrounding cube are the neighbours of a cell, the 3D
+/// counterpart of [`super::Neighbourhood`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Neighbourhood3 {
+    /// The 6 cells sharing a face.
+    Faces,
+    /// The 18 cells sharing a face or an edge.
+    Edges,
+    /// The 26 cells sharing a face, an edge or a corner.
+    Corners,
+}
+
+impl Neighbourhood3 {
+    fn max_moved_axes(&self) -> usize {
+        match self {
+            Neighbourhood3::Faces => 1,
+            Neighbourhood3::Edges => 2,
+            Neighbourhood3::Corners => 3,
+        }
+    }
+}
+
+impl<U> Add<&Vec3<U>> for &Coord3<U>
+where
+    U: Add<Output = U> + Copy,
+{
+    type Output = Coord3<U>;
+
+    fn add(self, rhs: &Vec3<U>) -> Self::Output {
+        Coord3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl<U> AddAssign<Vec3<U>> for Coord3<U>
+where
+    U: AddAssign<U>,
+{
+    fn add_assign(&mut self, rhs: Vec3<U>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<U> Sub for Coord3<U>
+where
+    U: Sub<Output = U>,
+{
+    type Output = Vec3<U>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Cannot parse 3d coord from \"{0}\", expecting \"x,y,z\"")]
+pub struct CannotParseCoord3(String);
+
+impl<U: FromStr> FromStr for Coord3<U> {
+    type Err = CannotParseCoord3;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split(',').map(|part| part.trim().parse()).collect_tuple() {
+            Some((Ok(x), Ok(y), Ok(z))) => Ok(Self { x, y, z }),
+            _ => Err(CannotParseCoord3(s.to_string())),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Vec3<U = i32> {
+    pub x: U,
+    pub y: U,
+    pub z: U,
+}
+
+impl<U> Vec3<U> {
+    pub fn new(x: U, y: U, z: U) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<U: PrimInt + Signed> Vec3<U> {
+    pub fn zero() -> Self {
+        Self::new(U::zero(), U::zero(), U::zero())
+    }
+
+    pub fn i() -> Self {
+        Self::new(U::one(), U::zero(), U::zero())
+    }
+
+    pub fn j() -> Self {
+        Self::new(U::zero(), U::one(), U::zero())
+    }
+
+    pub fn k() -> Self {
+        Self::new(U::zero(), U::zero(), U::one())
+    }
+
+    /// `i`, `j` and `k`.
+    pub fn axes() -> [Self; 3] {
+        [Self::i(), Self::j(), Self::k()]
+    }
+
+    pub fn dot(&self, rhs: &Self) -> U {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn cross(&self, rhs: &Self) -> Self {
+        Self {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+
+    pub fn manhattan_len(&self) -> U {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+}
+
+impl<U: Neg<Output = U>> Neg for Vec3<U> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<U: Add<Output = U>> Add for Vec3<U> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl<U: Sub<Output = U>> Sub for Vec3<U> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl<U: Mul<Output = U> + Copy> Mul<U> for Vec3<U> {
+    type Output = Self;
+
+    fn mul(self, rhs: U) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+/// One of the 24 rotations mapping axes onto axes (the orientations of a cube).
+///
+/// The rotated `i`, `j` and `k` are, in that order, `sign * e(axis)` for each
+/// `(axis, sign)` of `axes`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Rotation3 {
+    axes: [(usize, i8); 3],
+}
+
+impl Rotation3 {
+    pub fn identity() -> Self {
+        Self {
+            axes: [(0, 1), (1, 1), (2, 1)],
+        }
+    }
+
+    pub fn all() -> [Rotation3; 24] {
+        let rotations: Vec<_> = (0..3)
+            .permutations(3)
+            .cartesian_product(
+                (0..8)
+                    .map(|signs| [0, 1, 2].map(|bit| if signs & (1 << bit) == 0 { 1 } else { -1 })),
+            )
+            .map(|(permutation, signs)| Self {
+                axes: [0, 1, 2].map(|i| (permutation[i], signs[i])),
+            })
+            .filter(|rotation| rotation.determinant() == 1)
+            .collect();
+
+        rotations.try_into().unwrap()
+    }
+
+    pub fn apply<U: PrimInt + Signed>(&self, vec: &Vec3<U>) -> Vec3<U> {
+        let mut result = [U::zero(); 3];
+        for (component, (axis, sign)) in [vec.x, vec.y, vec.z].into_iter().zip(self.axes) {
+            result[axis] = result[axis] + component * U::from(sign).unwrap();
+        }
+        Vec3::new(result[0], result[1], result[2])
+    }
+
+    /// Rotation of `coord` around `center`.
+    pub fn apply_around<U: PrimInt + Signed>(
+        &self,
+        coord: &Coord3<U>,
+        center: &Coord3<U>,
+    ) -> Coord3<U> {
+        center + &self.apply(&(*coord - *center))
+    }
+
+    /// Rotation equivalent to applying `self` then `next`.
+    pub fn then(&self, next: &Rotation3) -> Rotation3 {
+        Self {
+            axes: self.axes.map(|(axis, sign)| {
+                let (next_axis, next_sign) = next.axes[axis];
+                (next_axis, sign * next_sign)
+            }),
+        }
+    }
+
+    pub fn inverse(&self) -> Rotation3 {
+        let mut axes = [(0, 1); 3];
+        for (from, (to, sign)) in self.axes.into_iter().enumerate() {
+            axes[to] = (from, sign);
+        }
+        Self { axes }
+    }
+
+    fn determinant(&self) -> i8 {
+        let [(a, sa), (b, sb), (c, sc)] = self.axes;
+        let inversions = [(a, b), (a, c), (b, c)]
+            .into_iter()
+            .filter(|(first, second)| first > second)
+            .count();
+        let parity = if inversions % 2 == 0 { 1 } else { -1 };
+        parity * sa * sb * sc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn neighbours_count() {
+        let coord = Coord3::new(1, -2, 3);
+        let count = |neighbourhood| {
+            coord
+                .neighbours(&neighbourhood)
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        assert_eq!(count(Neighbourhood3::Faces), 6);
+        assert_eq!(count(Neighbourhood3::Edges), 18);
+        assert_eq!(count(Neighbourhood3::Corners), 26);
+        assert!(coord
+            .neighbours(&Neighbourhood3::Faces)
+            .all(|n| n.manhattan_dist_to(&coord) == 1));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("2,-2,5".parse::<Coord3>().unwrap(), Coord3::new(2, -2, 5));
+        assert!("2,2".parse::<Coord3>().is_err());
+        assert!("2,2,5,1".parse::<Coord3>().is_err());
+    }
+
+    #[test]
+    fn products() {
+        let [i, j, k] = Vec3::<i32>::axes();
+        assert_eq!(i.cross(&j), k);
+        assert_eq!(j.cross(&i), -k);
+        assert_eq!(Vec3::new(1, 2, 3).dot(&Vec3::new(4, -5, 6)), 12);
+    }
+
+    #[test]
+    fn there_are_24_distinct_rotations() {
+        let v = Vec3::new(1, 2, 3);
+        let rotated: HashSet<_> = Rotation3::all()
+            .iter()
+            .map(|rotation| rotation.apply(&v))
+            .collect();
+        assert_eq!(rotated.len(), 24);
+    }
+
+    #[test]
+    fn rotations_keep_handedness() {
+        for rotation in Rotation3::all() {
+            let [i, j, k] = Vec3::<i32>::axes().map(|axis| rotation.apply(&axis));
+            assert_eq!(i.cross(&j), k);
+        }
+    }
+
+    #[test]
+    fn composition_and_inverse() {
+        let v = Vec3::new(1, 2, 3);
+        for a in Rotation3::all() {
+            assert_eq!(a.inverse().apply(&a.apply(&v)), v);
+            for b in Rotation3::all() {
+                assert_eq!(a.then(&b).apply(&v), b.apply(&a.apply(&v)));
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_around_a_center() {
+        let [i, j, k] = Vec3::<i32>::axes();
+        let quarter_turn_z = Rotation3::all()
+            .into_iter()
+            .find(|r| r.apply(&i) == j && r.apply(&k) == k)
+            .unwrap();
+        assert_eq!(
+            quarter_turn_z.apply_around(&Coord3::new(2, 1, 5), &Coord3::new(1, 1, 0)),
+            Coord3::new(1, 2, 5)
+        );
+    }
+}
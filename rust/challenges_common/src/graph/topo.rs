@@ -0,0 +1,323 @@
+use crate::graph::{Cost, Node, Path};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use thiserror::Error;
+
+/// Nodes forming a cycle, each one before the next and the last before the
+/// first.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Cannot order nodes: cycle between {} nodes", .nodes.len())]
+pub struct Cycle<N> {
+    pub nodes: Vec<N>,
+}
+
+/// Orders `nodes` so that, for every edge `(before, after)`, `before` comes
+/// first (Kahn's algorithm). Nodes only found in `edges` are included.
+///
+/// The sort is stable: nodes free to go are taken in the order they are first
+/// given, `nodes` then `edges`.
+pub fn topological_sort<N>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Node + Clone,
+{
+    let dag = Dag::new(nodes, edges.into_iter().map(|(from, to)| (from, to, ())));
+    let order = dag.order()?;
+    Ok(order.into_iter().map(|i| dag.nodes[i].clone()).collect())
+}
+
+/// Stable sort of `items` under `rules` `(before, after)`, ignoring the rules
+/// about items not in `items`.
+pub fn sort_by_rules<N>(
+    items: &[N],
+    rules: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Node + Clone,
+{
+    let present: HashSet<_> = items.iter().collect();
+    let rules: Vec<_> = rules
+        .into_iter()
+        .filter(|(before, after)| present.contains(before) && present.contains(after))
+        .collect();
+    topological_sort(items.iter().cloned(), rules)
+}
+
+/// Costliest path following the weighted `edges` `(from, to, cost)` of a DAG,
+/// `None` without any node.
+pub fn longest_path<N, C>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N, C)>,
+) -> Result<Option<Path<N, C>>, Cycle<N>>
+where
+    N: Node + Clone,
+    C: Cost,
+{
+    let dag = Dag::new(nodes, edges);
+    let order = dag.order()?;
+
+    let mut best: Vec<(C, Option<usize>)> = vec![(C::default(), None); dag.nodes.len()];
+    for &from in &order {
+        let cost = best[from].0;
+        for &(to, additional_cost) in &dag.successors[from] {
+            if best[to].1.is_none() || cost + additional_cost > best[to].0 {
+                best[to] = (cost + additional_cost, Some(from));
+            }
+        }
+    }
+
+    let Some(end) = (0..dag.nodes.len()).max_by_key(|&i| best[i].0) else {
+        return Ok(None);
+    };
+    let mut nodes = vec![dag.nodes[end].clone()];
+    let mut current = end;
+    while let Some(previous) = best[current].1 {
+        nodes.push(dag.nodes[previous].clone());
+        current = previous;
+    }
+    nodes.reverse();
+
+    Ok(Some(Path {
+        nodes,
+        cost: best[end].0,
+    }))
+}
+
+/// Evaluates every node of `nodes` after the ones it depends on, starting with
+/// the `known` values. Nodes depending on a node neither known nor in `nodes`
+/// are left out.
+pub fn evaluate<N, T, V, Deps>(
+    nodes: &HashMap<N, T>,
+    known: HashMap<N, V>,
+    dependencies: impl Fn(&T) -> Deps,
+    mut eval: impl FnMut(&T, &[&V]) -> V,
+) -> Result<HashMap<N, V>, Cycle<N>>
+where
+    N: Node + Clone,
+    Deps: IntoIterator<Item = N>,
+{
+    let dependencies: HashMap<&N, Vec<N>> = nodes
+        .iter()
+        .map(|(node, content)| (node, dependencies(content).into_iter().collect()))
+        .collect();
+    let edges: Vec<_> = dependencies
+        .iter()
+        .flat_map(|(&node, node_dependencies)| {
+            node_dependencies
+                .iter()
+                .filter(|dependency| nodes.contains_key(dependency))
+                .map(move |dependency| (dependency.clone(), node.clone()))
+        })
+        .collect();
+    let order = topological_sort(nodes.keys().cloned(), edges)?;
+
+    let mut values = known;
+    for node in order {
+        if values.contains_key(&node) {
+            continue;
+        }
+        let value = {
+            let inputs: Option<Vec<_>> = dependencies[&node]
+                .iter()
+                .map(|dependency| values.get(dependency))
+                .collect();
+            inputs.map(|inputs| eval(&nodes[&node], &inputs))
+        };
+        if let Some(value) = value {
+            values.insert(node, value);
+        }
+    }
+
+    Ok(values)
+}
+
+/// Nodes by index, in the order they are first given.
+struct Dag<N, C> {
+    nodes: Vec<N>,
+    successors: Vec<Vec<(usize, C)>>,
+}
+
+impl<N: Node + Clone, C> Dag<N, C> {
+    fn new(nodes: impl IntoIterator<Item = N>, edges: impl IntoIterator<Item = (N, N, C)>) -> Self {
+        let mut dag = Self {
+            nodes: Vec::new(),
+            successors: Vec::new(),
+        };
+        let mut indexes = HashMap::new();
+        let mut index_of = |node: N, dag: &mut Self| {
+            *indexes.entry(node.clone()).or_insert_with(|| {
+                dag.nodes.push(node);
+                dag.successors.push(Vec::new());
+                dag.nodes.len() - 1
+            })
+        };
+
+        for node in nodes {
+            index_of(node, &mut dag);
+        }
+        for (from, to, cost) in edges {
+            let from = index_of(from, &mut dag);
+            let to = index_of(to, &mut dag);
+            dag.successors[from].push((to, cost));
+        }
+        dag
+    }
+
+    /// Indexes of the nodes, each one after its predecessors, the first given
+    /// going first when free to.
+    fn order(&self) -> Result<Vec<usize>, Cycle<N>> {
+        let mut remaining = vec![0; self.nodes.len()];
+        for successors in &self.successors {
+            for &(to, _) in successors {
+                remaining[to] += 1;
+            }
+        }
+
+        let mut free: BinaryHeap<_> = (0..self.nodes.len())
+            .filter(|&i| remaining[i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(Reverse(from)) = free.pop() {
+            order.push(from);
+            for &(to, _) in &self.successors[from] {
+                remaining[to] -= 1;
+                if remaining[to] == 0 {
+                    free.push(Reverse(to));
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            return Err(self.cycle(&remaining));
+        }
+        Ok(order)
+    }
+
+    /// A cycle among the nodes `order` could not free.
+    fn cycle(&self, remaining: &[usize]) -> Cycle<N> {
+        // every node left has a predecessor left: walking them back loops on a cycle
+        let predecessor = |to: usize| {
+            (0..self.nodes.len()).find(|&from| {
+                remaining[from] > 0 && self.successors[from].iter().any(|&(t, _)| t == to)
+            })
+        };
+
+        let mut current = (0..self.nodes.len()).find(|&i| remaining[i] > 0).unwrap();
+        let mut walked = vec![current];
+        let mut positions = HashMap::from([(current, 0)]);
+        loop {
+            current = predecessor(current).unwrap();
+            if let Some(&start) = positions.get(&current) {
+                return Cycle {
+                    nodes: walked[start..]
+                        .iter()
+                        .rev()
+                        .map(|&i| self.nodes[i].clone())
+                        .collect(),
+                };
+            }
+            positions.insert(current, walked.len());
+            walked.push(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorts_stably() {
+        let sorted = topological_sort([5, 4, 3, 2, 1], [(1, 5), (2, 4)]).unwrap();
+        assert_eq!(sorted, vec![3, 2, 4, 1, 5]);
+
+        let sorted = topological_sort([], [("b", "c"), ("a", "b")]).unwrap();
+        assert_eq!(sorted, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn reports_a_cycle() {
+        let error = topological_sort([0], [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]).unwrap_err();
+
+        assert_eq!(error.nodes, vec![2, 3, 1]);
+        assert_eq!(
+            error.to_string(),
+            "Cannot order nodes: cycle between 3 nodes"
+        );
+    }
+
+    #[test]
+    fn sorts_a_subset_under_rules() {
+        let rules = [
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (75, 29),
+            (61, 13),
+            (29, 13),
+            (97, 47),
+        ];
+
+        assert_eq!(
+            sort_by_rules(&[61, 13, 29], rules).unwrap(),
+            vec![61, 29, 13]
+        );
+        assert_eq!(
+            sort_by_rules(&[97, 13, 75, 29, 47], rules).unwrap(),
+            vec![97, 75, 29, 13, 47]
+        );
+    }
+
+    #[test]
+    fn longest_path_in_a_dag() {
+        let edges = [(0, 1, 3), (0, 2, 1), (2, 1, 5), (1, 3, 1), (4, 3, 2)];
+        let path = longest_path([], edges).unwrap().unwrap();
+
+        assert_eq!((path.nodes, path.cost), (vec![0, 2, 1, 3], 7));
+        assert!(longest_path::<u8, u8>([], []).unwrap().is_none());
+        assert!(longest_path([], [(0, 1, 1), (1, 0, 1)]).is_err());
+    }
+
+    #[test]
+    fn evaluates_in_dependency_order() {
+        let nodes = HashMap::from([
+            ("sum", vec!["a", "double"]),
+            ("double", vec!["a", "a"]),
+            ("orphan", vec!["missing"]),
+        ]);
+        let known = HashMap::from([("a", 3)]);
+
+        let values = evaluate(
+            &nodes,
+            known,
+            |inputs| inputs.clone(),
+            |_, inputs| inputs.iter().copied().sum(),
+        )
+        .unwrap();
+
+        assert_eq!(values, HashMap::from([("a", 3), ("double", 6), ("sum", 9)]));
+    }
+
+    #[test]
+    fn evaluate_asks_dependencies_once_per_node() {
+        let nodes = HashMap::from([("b", vec!["a"]), ("c", vec!["a", "b"])]);
+        let asked = std::cell::Cell::new(0);
+
+        let values = evaluate(
+            &nodes,
+            HashMap::from([("a", 1)]),
+            |inputs| {
+                asked.set(asked.get() + 1);
+                inputs.clone()
+            },
+            |_, inputs| inputs.iter().copied().sum(),
+        )
+        .unwrap();
+
+        assert_eq!(values[&"c"], 2);
+        assert_eq!(asked.get(), 2);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::graph::Node;
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Breadth-first traversal from `start`, yielding `(node, depth, parent)`.
+///
+/// Nodes are visited once by default, see [`Traversal::visit_all`] and
+/// [`Traversal::visited_by`]. The traversal is lazy: the nexts of a node are
+/// only asked for when the following node is requested, so stopping the
+/// iteration (`find`, `take_while`…) stops the search.
+pub fn bfs<N, F, Nexts>(start: N, next: F) -> Traversal<N, F>
+where
+    N: Node + Clone,
+    F: FnMut(&N) -> Nexts,
+    Nexts: IntoIterator<Item = N>,
+{
+    Traversal::new(start, next, false)
+}
+
+/// Depth-first traversal from `start`, nexts being explored in the order they
+/// are given. See [`bfs`].
+pub fn dfs<N, F, Nexts>(start: N, next: F) -> Traversal<N, F>
+where
+    N: Node + Clone,
+    F: FnMut(&N) -> Nexts,
+    Nexts: IntoIterator<Item = N>,
+{
+    Traversal::new(start, next, true)
+}
+
+pub struct Traversal<N, F, V = VisitByNode<N>> {
+    frontier: VecDeque<(N, usize, Option<N>)>,
+    to_expand: Option<(N, usize)>,
+    next: F,
+    visited: V,
+    depth_first: bool,
+    max_depth: Option<usize>,
+}
+
+impl<N, F> Traversal<N, F>
+where
+    N: Node + Clone,
+{
+    fn new(start: N, next: F, depth_first: bool) -> Self {
+        Self {
+            frontier: VecDeque::from([(start, 0, None)]),
+            to_expand: None,
+            next,
+            visited: VisitByNode(HashSet::new()),
+            depth_first,
+            max_depth: None,
+        }
+    }
+}
+
+impl<N, F, V> Traversal<N, F, V> {
+    /// Does not track visited nodes: a node is yielded every time it is
+    /// reached, the graph must then be acyclic or the traversal bounded.
+    pub fn visit_all(self) -> Traversal<N, F, VisitAll> {
+        self.with_visited(VisitAll)
+    }
+
+    /// Visits only once the nodes having the same `key`.
+    pub fn visited_by<K, P>(self, key: P) -> Traversal<N, F, VisitByKey<N, K, P>>
+    where
+        P: Fn(&N) -> K,
+    {
+        self.with_visited(VisitByKey {
+            seen: HashSet::new(),
+            key,
+            node: PhantomData,
+        })
+    }
+
+    /// Does not look for nexts of nodes at `max_depth`.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    fn with_visited<W>(self, visited: W) -> Traversal<N, F, W> {
+        Traversal {
+            frontier: self.frontier,
+            to_expand: self.to_expand,
+            next: self.next,
+            visited,
+            depth_first: self.depth_first,
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+impl<N, F, Nexts, V> Iterator for Traversal<N, F, V>
+where
+    N: Clone,
+    F: FnMut(&N) -> Nexts,
+    Nexts: IntoIterator<Item = N>,
+    V: Visited<N>,
+{
+    type Item = (N, usize, Option<N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((node, depth)) = self.to_expand.take() {
+            let nexts = (self.next)(&node)
+                .into_iter()
+                .map(|next| (next, depth + 1, Some(node.clone())));
+            if self.depth_first {
+                let nexts: Vec<_> = nexts.collect();
+                nexts
+                    .into_iter()
+                    .rev()
+                    .for_each(|next| self.frontier.push_back(next));
+            } else {
+                self.frontier.extend(nexts);
+            }
+        }
+
+        loop {
+            let (node, depth, parent) = if self.depth_first {
+                self.frontier.pop_back()?
+            } else {
+                self.frontier.pop_front()?
+            };
+
+            if self.visited.first_visit(&node) {
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    self.to_expand = Some((node.clone(), depth));
+                }
+                return Some((node, depth, parent));
+            }
+        }
+    }
+}
+
+/// How a [`Traversal`] avoids visiting a node twice.
+pub trait Visited<N> {
+    /// Records `node` as visited, returns whether it was not already.
+    fn first_visit(&mut self, node: &N) -> bool;
+}
+
+pub struct VisitAll;
+
+impl<N> Visited<N> for VisitAll {
+    fn first_visit(&mut self, _: &N) -> bool {
+        true
+    }
+}
+
+pub struct VisitByNode<N>(HashSet<N>);
+
+impl<N: Node + Clone> Visited<N> for VisitByNode<N> {
+    fn first_visit(&mut self, node: &N) -> bool {
+        self.0.insert(node.clone())
+    }
+}
+
+pub struct VisitByKey<N, K, P> {
+    seen: HashSet<K>,
+    key: P,
+    node: PhantomData<fn(&N)>,
+}
+
+impl<N, K, P> Visited<N> for VisitByKey<N, K, P>
+where
+    K: Hash + Eq,
+    P: Fn(&N) -> K,
+{
+    fn first_visit(&mut self, node: &N) -> bool {
+        self.seen.insert((self.key)(node))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tree(n: &u32) -> Vec<u32> {
+        if *n < 4 {
+            vec![2 * n, 2 * n + 1]
+        } else {
+            vec![]
+        }
+    }
+
+    #[test]
+    fn bfs_by_depth() {
+        let visits: Vec<_> = bfs(1, tree).collect();
+        assert_eq!(
+            visits.iter().map(|(n, _, _)| *n).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(visits[0], (1, 0, None));
+        assert_eq!(visits[5], (6, 2, Some(3)));
+    }
+
+    #[test]
+    fn dfs_keeps_nexts_order() {
+        let nodes: Vec<_> = dfs(1, tree).map(|(n, _, _)| n).collect();
+        assert_eq!(nodes, vec![1, 2, 4, 5, 3, 6, 7]);
+    }
+
+    #[test]
+    fn cycles_are_visited_once() {
+        let ring = |n: &u32| [(n + 1) % 5, (n + 4) % 5];
+        assert_eq!(bfs(0, ring).count(), 5);
+        assert_eq!(bfs(0, ring).visited_by(|n| n % 2).count(), 2);
+        assert_eq!(bfs(0, ring).visit_all().max_depth(2).count(), 7);
+    }
+
+    #[test]
+    fn stops_asking_for_nexts_once_found() {
+        let mut asked = Vec::new();
+        let found = bfs(1, |n: &u32| {
+            asked.push(*n);
+            tree(n)
+        })
+        .find(|(n, _, _)| *n == 3);
+
+        assert_eq!(found, Some((3, 1, Some(1))));
+        assert_eq!(asked, vec![1, 2]);
+    }
+}
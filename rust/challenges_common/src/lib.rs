@@ -1,4 +1,5 @@
 #![recursion_limit = "256"]
+pub mod automaton;
 pub mod cycle;
 pub mod geom;
 pub mod graph;